@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::warn;
+use reqwest::Client;
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+use crate::config::MasterConfig;
+use crate::core::DillingerCore;
+use crate::game_manager::GameCacheEntry;
+
+const DEFAULT_PEER_TIMEOUT_MS: u64 = 2000;
+const DEFAULT_MAX_CONCURRENT_PEERS: usize = 4;
+
+/// Peers to fan `search/local` queries out to, plus how patient to be with
+/// each one before giving up on it.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ClusterConfig {
+    #[serde(default)]
+    pub peers: Vec<String>,
+    #[serde(default = "default_peer_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_max_concurrent_peers")]
+    pub max_concurrent: usize,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            peers: Vec::new(),
+            timeout_ms: DEFAULT_PEER_TIMEOUT_MS,
+            max_concurrent: DEFAULT_MAX_CONCURRENT_PEERS,
+        }
+    }
+}
+
+fn default_peer_timeout_ms() -> u64 {
+    DEFAULT_PEER_TIMEOUT_MS
+}
+
+fn default_max_concurrent_peers() -> usize {
+    DEFAULT_MAX_CONCURRENT_PEERS
+}
+
+/// A game cache entry tagged with the node it was found on, so the UI can
+/// show (and eventually target) the node that actually holds the ROM files.
+#[derive(Serialize, Debug)]
+pub struct ClusteredGameCacheEntry {
+    #[serde(flatten)]
+    pub entry: GameCacheEntry,
+    pub origin: String,
+}
+
+/// Searches this node's own cache plus every configured peer's
+/// `search/local/{term}` endpoint concurrently, merging everything into one
+/// list. Peers that time out or error are skipped rather than failing the
+/// whole request.
+pub async fn search_cluster(core: &Arc<DillingerCore>, term: &str) -> Vec<ClusteredGameCacheEntry> {
+    let mut results: Vec<ClusteredGameCacheEntry> = {
+        let cache = core.game_cache.read().await;
+        cache
+            .entries
+            .iter()
+            .filter(|entry| entry.slug.contains(term))
+            .map(|entry| ClusteredGameCacheEntry {
+                entry: entry.clone(),
+                origin: "local".to_string(),
+            })
+            .collect()
+    };
+
+    results.extend(search_peers(&core.config, term).await);
+    results
+}
+
+async fn search_peers(config: &MasterConfig, term: &str) -> Vec<ClusteredGameCacheEntry> {
+    let semaphore = Arc::new(Semaphore::new(config.cluster.max_concurrent.max(1)));
+    let client = Client::new();
+    let timeout = Duration::from_millis(config.cluster.timeout_ms);
+
+    let tasks = config.cluster.peers.iter().map(|peer| {
+        let semaphore = Arc::clone(&semaphore);
+        let client = client.clone();
+        let peer = peer.clone();
+        let term = term.to_string();
+        async move {
+            let _permit = semaphore.acquire().await.ok()?;
+            query_peer(&client, &peer, &term, timeout).await
+        }
+    });
+
+    futures::future::join_all(tasks)
+        .await
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect()
+}
+
+async fn query_peer(
+    client: &Client,
+    peer: &str,
+    term: &str,
+    timeout: Duration,
+) -> Option<Vec<ClusteredGameCacheEntry>> {
+    let url = format!(
+        "{}/search/local/{}",
+        peer.trim_end_matches('/'),
+        urlencoding::encode(term)
+    );
+
+    let response = match tokio::time::timeout(timeout, client.get(&url).send()).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            warn!("Cluster peer {} errored: {:?}", peer, e);
+            return None;
+        }
+        Err(_) => {
+            warn!("Cluster peer {} timed out after {:?}", peer, timeout);
+            return None;
+        }
+    };
+
+    match response.json::<Vec<GameCacheEntry>>().await {
+        Ok(entries) => Some(
+            entries
+                .into_iter()
+                .map(|entry| ClusteredGameCacheEntry {
+                    entry,
+                    origin: peer.to_string(),
+                })
+                .collect(),
+        ),
+        Err(e) => {
+            warn!("Cluster peer {} returned unparseable JSON: {:?}", peer, e);
+            None
+        }
+    }
+}
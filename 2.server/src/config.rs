@@ -3,7 +3,13 @@ use serde::{Deserialize, Serialize};
 use std::{env, path::PathBuf, sync::Arc};
 use toml;
 
+use crate::cluster::ClusterConfig;
+use crate::docker::docker_wine_runner::RunnerConfig;
+use crate::gamedb::registry::GameDbProviderConfig;
+use crate::network::transfer_repo::TransferRepoBackend;
 use crate::platform::Platform;
+use crate::scrapers::scrapers::GamedbSection;
+use crate::storage::{ObjectStoreConfig, StoreBackend};
 
 pub const DILLINGER_ROOT_DIR: &str = "DILLINGER_ROOT_DIR";
 pub const WINE_RUNNER_NAME: &str = "dillinger-wine:latest";
@@ -15,6 +21,73 @@ pub struct MasterConfig {
     pub entries_dir: PathBuf,
     pub platforms: Vec<Platform>,
     pub chunking_interval: u64,
+    #[serde(default = "default_docker_pool_size")]
+    pub docker_pool_size: u32,
+    /// Overrides where to find the container daemon, e.g.
+    /// `unix:///var/run/docker.sock` or `tcp://127.0.0.1:2375`. Falls back to
+    /// `DOCKER_HOST`/`CONTAINER_HOST` and finally a rootless podman socket
+    /// when unset - see `docker::pool::resolve_host`.
+    #[serde(default)]
+    pub docker_host: Option<String>,
+    #[serde(default)]
+    pub cluster: ClusterConfig,
+    /// Wine/DXVK version pins for the Wine runner, so different games can
+    /// target different toolchains - see `docker::docker_wine_runner`.
+    #[serde(default)]
+    pub runner: RunnerConfig,
+    /// Which store persists in-flight file transfers - see
+    /// `network::transfer_repo::TransferRepo`.
+    #[serde(default)]
+    pub transfer_repo_backend: TransferRepoBackend,
+    /// Whole-request deadline, in seconds, before a file transfer is given up
+    /// on as failed regardless of how much it's downloaded so far.
+    #[serde(default = "default_transfer_deadline_secs")]
+    pub transfer_deadline_secs: u64,
+    /// How long, in seconds, a file transfer can go without receiving any
+    /// bytes before it's considered stalled and failed.
+    #[serde(default = "default_transfer_idle_timeout_secs")]
+    pub transfer_idle_timeout_secs: u64,
+    /// Where downloads and scraped assets are written - see
+    /// `storage::Store`.
+    #[serde(default)]
+    pub store_backend: StoreBackend,
+    /// Required when `store_backend = Object`.
+    #[serde(default)]
+    pub object_store: Option<ObjectStoreConfig>,
+    /// How long, in seconds, a cached `GameDb` search/game-data response stays
+    /// fresh before a lookup re-fetches it - see `gamedb::gamedb_cache`.
+    #[serde(default = "default_gamedb_cache_ttl_secs")]
+    pub gamedb_cache_ttl_secs: u64,
+    /// When set, `GameDb` lookups are served only from the disk cache and
+    /// never call out to the network - so the UI keeps working, with
+    /// possibly stale data, when IGDB or the Twitch token endpoint is
+    /// unreachable.
+    #[serde(default)]
+    pub gamedb_offline_mode: bool,
+    /// Which `GameDb` backends are enabled, and their credentials/endpoints -
+    /// see `gamedb::registry`.
+    #[serde(default)]
+    pub game_dbs: Vec<GameDbProviderConfig>,
+    /// `[gamedb.igdb]` credentials for the legacy `scrapers::igdb` client -
+    /// see `scrapers::scrapers::IgdbCredentials`.
+    #[serde(default)]
+    pub gamedb: GamedbSection,
+}
+
+fn default_docker_pool_size() -> u32 {
+    4
+}
+
+fn default_transfer_deadline_secs() -> u64 {
+    3600
+}
+
+fn default_transfer_idle_timeout_secs() -> u64 {
+    30
+}
+
+fn default_gamedb_cache_ttl_secs() -> u64 {
+    60 * 60 * 24 // 1 day
 }
 
 impl MasterConfig {
@@ -23,34 +96,80 @@ impl MasterConfig {
     }
 }
 
-pub fn get_master_config() -> Arc<MasterConfig> {
-    // Start by finding out where to look for the master config
+/// Where `dillinger_config.toml` is expected to live, honoring
+/// `DILLINGER_ROOT_DIR` and falling back to the current directory.
+fn config_path() -> String {
     let config_dir = env::var(DILLINGER_ROOT_DIR).unwrap_or_else(|_| {
         info!("DILLINGER_ROOT_DIR is not set, trying the current directory");
         ".".to_string()
     });
+    format!("{}/dillinger_config.toml", config_dir)
+}
 
-    // Second, load the file
-    let config_path = format!("{}/dillinger_config.toml", config_dir);
-    info!("Looking for the master config file at: {}", config_path);
-    let content = std::fs::read_to_string(&config_path)
-    .unwrap_or_else(|_| { panic!("Could not load master config file.") });
+/// Reads and parses `path` into a `MasterConfig`, computing derived paths
+/// like `entries_dir`. Used both for the initial, must-succeed load and for
+/// hot-reloads, where a parse failure is reported back to the caller instead
+/// of panicking.
+fn load_master_config(path: &str) -> Result<MasterConfig, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path, e))?;
 
-    // Parse the content into a MasterConfig
     debug!("Parsing master config file: {:?}", content);
     let mut master_config: MasterConfig =
-        toml::from_str(&content)
-        .unwrap_or_else(|_| { panic!("Could not parse master config file.") });
+        toml::from_str(&content).map_err(|e| format!("Could not parse {}: {}", path, e))?;
 
-    // And calculate any paths we need
     master_config.set_entries_dir();
+    Ok(master_config)
+}
 
-    let master_config : Arc<MasterConfig> = Arc::new(master_config);
-    
-    
-    // Finally, if we got the config, we know our root dir
-    // master_config.root_dir = PathBuf::from(config_path);
+pub fn get_master_config() -> Arc<MasterConfig> {
+    let config_path = config_path();
+    info!("Looking for the master config file at: {}", config_path);
+
+    let master_config = load_master_config(&config_path)
+        .unwrap_or_else(|e| panic!("Could not load master config file: {}", e));
 
-    // Yay!
-    master_config
+    Arc::new(master_config)
 }
+
+/// Spawns a background task that polls `dillinger_config.toml`'s mtime every
+/// `CONFIG_RELOAD_POLL_SECS` and, whenever it changes, re-parses the file and
+/// atomically swaps it into `live` - so config edits take effect without a
+/// restart. A malformed edit is logged and otherwise ignored; the
+/// last-known-good config keeps serving.
+pub fn watch_for_config_changes(live: &'static arc_swap::ArcSwap<MasterConfig>) {
+    let config_path = config_path();
+
+    tokio::spawn(async move {
+        let mut last_modified = std::fs::metadata(&config_path).and_then(|m| m.modified()).ok();
+
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(CONFIG_RELOAD_POLL_SECS)).await;
+
+            let modified = match std::fs::metadata(&config_path).and_then(|m| m.modified()) {
+                Ok(modified) => modified,
+                Err(e) => {
+                    debug!("Could not stat {} for hot-reload: {}", config_path, e);
+                    continue;
+                }
+            };
+            if Some(modified) == last_modified {
+                continue;
+            }
+            last_modified = Some(modified);
+
+            match load_master_config(&config_path) {
+                Ok(new_config) => {
+                    info!("Reloaded {} after it changed on disk", config_path);
+                    live.store(Arc::new(new_config));
+                }
+                Err(e) => {
+                    log::warn!("Ignoring invalid {} edit: {}", config_path, e);
+                }
+            }
+        }
+    });
+}
+
+/// How often the config file's mtime is checked for hot-reload.
+const CONFIG_RELOAD_POLL_SECS: u64 = 5;
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use warp::Filter;
+
+use crate::config::MasterConfig;
+use crate::docker::pool::{self, DockerPool};
+use crate::docker::runtime::{BollardRuntime, ContainerRuntime};
+use crate::game_manager::GameCacheEntries;
+use crate::media_store::{self, MediaStore};
+
+/// Owns the process-wide state that used to live behind `lazy_static`
+/// globals - the config, the game cache, and the docker connection pool -
+/// so it can be constructed once in `main` and handed to handlers instead of
+/// each of them reaching for a global. In particular this swaps the
+/// synchronous `std::sync::Mutex` around the game cache for a `tokio::sync::RwLock`,
+/// so reading it in an async handler can no longer stall a Tokio worker.
+pub struct DillingerCore {
+    pub config: Arc<MasterConfig>,
+    pub game_cache: RwLock<GameCacheEntries>,
+    pub docker_pool: DockerPool,
+    pub runtime: Box<dyn ContainerRuntime>,
+    pub media_store: Arc<dyn MediaStore>,
+}
+
+impl DillingerCore {
+    pub async fn new(config: Arc<MasterConfig>) -> Arc<Self> {
+        let docker_pool = pool::pool().await.clone();
+        let media_store = media_store::open(&config);
+        Arc::new(Self {
+            config,
+            game_cache: RwLock::new(GameCacheEntries::from(Vec::new())),
+            docker_pool,
+            runtime: Box::new(BollardRuntime),
+            media_store,
+        })
+    }
+}
+
+/// A warp filter clause that hands a clone of `core` to the handler it's
+/// `.and()`-ed onto, e.g. `route.and(with_core(core.clone()))`.
+pub fn with_core(
+    core: Arc<DillingerCore>,
+) -> impl Filter<Extract = (Arc<DillingerCore>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || core.clone())
+}
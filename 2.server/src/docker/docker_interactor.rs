@@ -1,36 +1,36 @@
 use bollard::{
-    container::{Config, CreateContainerOptions, ListContainersOptions, StartContainerOptions},
+    container::{
+        Config, CreateContainerOptions, DownloadFromContainerOptions, ListContainersOptions,
+        LogOutput, LogsOptions, RemoveContainerOptions, StartContainerOptions, StatsOptions,
+        UploadToContainerOptions,
+    },
     exec::{CreateExecOptions, StartExecResults},
+    service::{HostConfig, PortBinding},
+    system::EventsOptions,
     volume::CreateVolumeOptions,
-    Docker, API_DEFAULT_VERSION,
+    Docker,
 };
-use lazy_static::lazy_static;
-use log::{debug, info};
+use chrono::Utc;
+use futures::StreamExt;
+use tracing::{debug, info};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::Mutex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
 
 use super::volumes::DockerVolume;
 use super::volumes::VolumeContents;
+use crate::config::MasterConfig;
+use crate::entities::container_stats::ContainerStatsMessage;
+use crate::entities::playstats::PlayStats;
+use crate::handlers::socket_client;
 use crate::helpers::docker_run_params::DockerRunParams;
-
-// lazy_static! {
-//     static ref DOCKER: Arc<Mutex<Docker>> =
-//         Arc::new(Mutex::new(Docker::connect_with_local_defaults().unwrap()));
-// }
-
-const PODMAN_SOCKET: &str = "unix:///run/user/1000/podman/podman.sock";
-
-lazy_static! {
-    static ref DOCKER: Arc<Mutex<Docker>> = Arc::new(Mutex::new(
-        Docker::connect_with_unix(PODMAN_SOCKET, 120, API_DEFAULT_VERSION).unwrap()
-    ));
-}
+use crate::manifest_manager::ManifestManager;
 
 pub async fn get_docker_daemon_status() -> DockerStatus {
     info!("checking docker");
-    let docker = Arc::clone(&DOCKER);
-    let docker = docker.lock().await;
+    let docker = super::pool::get().await;
 
     match docker.version().await {
         Ok(_) => {
@@ -49,8 +49,7 @@ pub async fn get_docker_daemon_status() -> DockerStatus {
 }
 
 pub async fn list_named_volumes() -> Result<Vec<DockerVolume>, DockerError> {
-    let docker = Arc::clone(&DOCKER);
-    let docker = docker.lock().await;
+    let docker = super::pool::get().await;
 
     let volumes = docker.list_volumes::<String>(None).await;
 
@@ -76,54 +75,347 @@ pub async fn list_named_volumes() -> Result<Vec<DockerVolume>, DockerError> {
     }
 }
 
+const VOLUME_READER_CONTAINER: &str = "dillinger_volume_reader";
+const VOLUME_MOUNT_POINT: &str = "/volume_data";
+
+/// Lists the folders and files at `path` inside `volume_name`, using a
+/// throwaway alpine container to mount the volume and run `ls` in it.
 pub async fn get_volume_contents(
     volume_name: String,
     path: String,
 ) -> Result<Vec<VolumeContents>, DockerError> {
-    let docker = Arc::clone(&DOCKER);
-    let docker = docker.lock().await;
+    list_volume(volume_name, path, false).await
+}
 
-    let volumes = docker.list_volumes::<String>(None).await;
+/// Same as `get_volume_contents`, but runs a `stat`-style listing that also
+/// captures each file's size, so the UI can show per-save-file sizes for a
+/// game's named volume.
+pub async fn get_volume_contents_with_sizes(
+    volume_name: String,
+    path: String,
+) -> Result<Vec<VolumeContents>, DockerError> {
+    list_volume(volume_name, path, true).await
+}
+
+async fn list_volume(
+    volume_name: String,
+    path: String,
+    with_sizes: bool,
+) -> Result<Vec<VolumeContents>, DockerError> {
+    let docker = super::pool::get().await;
 
-    // Create a container with the volume mounted
-    let container_name = "dillinger_volume_reader";
     let config = Config {
         image: Some("alpine"),
-        volumes: Some(HashMap::from([(volume_name.as_str(), HashMap::new())])),
+        host_config: Some(HostConfig {
+            binds: Some(vec![format!("{}:{}", volume_name, VOLUME_MOUNT_POINT)]),
+            ..Default::default()
+        }),
+        cmd: Some(vec!["sleep".to_string(), "300".to_string()]),
         ..Default::default()
     };
 
-    let container = docker
+    docker
         .create_container(
             Some(CreateContainerOptions {
-                name: container_name,
+                name: VOLUME_READER_CONTAINER,
                 platform: Some("linux".to_string().as_str()),
             }),
             config,
         )
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error creating volume reader container: {:?}", e),
+        })?;
+
+    // Whatever happens below, the throwaway container must not be leaked.
+    let result = run_volume_listing(&docker, &path, with_sizes).await;
+
+    let _ = docker
+        .remove_container(
+            VOLUME_READER_CONTAINER,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
         .await;
 
+    result
+}
+
+async fn run_volume_listing(
+    docker: &Docker,
+    path: &str,
+    with_sizes: bool,
+) -> Result<Vec<VolumeContents>, DockerError> {
     docker
-        .start_container(container_name, None::<StartContainerOptions<String>>)
-        .await;
+        .start_container(
+            VOLUME_READER_CONTAINER,
+            None::<StartContainerOptions<String>>,
+        )
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error starting volume reader container: {:?}", e),
+        })?;
+
+    let target = format!("{}/{}", VOLUME_MOUNT_POINT, path.trim_start_matches('/'));
+    let ls_flags = if with_sizes { "-la" } else { "-1p" };
 
     let exec = docker
         .create_exec(
-            container_name,
+            VOLUME_READER_CONTAINER,
             CreateExecOptions {
                 attach_stdout: Some(true),
                 attach_stderr: Some(true),
-                cmd: Some(vec!["ls", "-1", &format!("/{}", volume_name)]),
+                cmd: Some(vec!["ls", ls_flags, &target]),
                 ..Default::default()
             },
         )
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error creating listing exec: {:?}", e),
+        })?;
+
+    let mut output_text = String::new();
+    match docker
+        .start_exec(&exec.id, None)
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error starting listing exec: {:?}", e),
+        })? {
+        StartExecResults::Attached { mut output, .. } => {
+            while let Some(chunk) = output.next().await {
+                match chunk {
+                    Ok(LogOutput::StdOut { message }) | Ok(LogOutput::StdErr { message }) => {
+                        output_text.push_str(&String::from_utf8_lossy(&message));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        StartExecResults::Detached => {}
+    }
+
+    let contents = if with_sizes {
+        parse_ls_with_sizes(&output_text)
+    } else {
+        parse_ls_plain(&output_text)
+    };
+
+    Ok(vec![contents])
+}
+
+fn parse_ls_plain(output: &str) -> VolumeContents {
+    let mut contents = VolumeContents::new();
+    for line in output.lines() {
+        let name = line.trim();
+        if name.is_empty() || name == "." || name == ".." {
+            continue;
+        }
+        if name.ends_with('/') {
+            contents.folders.push(name.trim_end_matches('/').to_string());
+        } else {
+            contents.files.push(name.to_string());
+        }
+    }
+    contents
+}
+
+// Parses `ls -la` output: `perms links owner group size month day time/year name`
+fn parse_ls_with_sizes(output: &str) -> VolumeContents {
+    let mut contents = VolumeContents::new();
+    let mut file_sizes = HashMap::new();
+
+    for line in output.lines() {
+        if line.starts_with("total") {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 9 {
+            continue;
+        }
+        let name = fields[8..].join(" ");
+        if name == "." || name == ".." {
+            continue;
+        }
+        let size: u64 = fields[4].parse().unwrap_or(0);
+
+        if fields[0].starts_with('d') {
+            contents.folders.push(name);
+        } else {
+            contents.files.push(name.clone());
+            file_sizes.insert(name, size);
+        }
+    }
+
+    contents.file_sizes = Some(file_sizes);
+    contents
+}
+
+const SAVE_BACKUP_CONTAINER: &str = "dillinger_save_backup";
+
+/// Streams the contents of a named volume out to `dest` as a `.tar` archive,
+/// using bollard's copy-from-container endpoint against a throwaway helper
+/// container that mounts the volume - the only way to reach a volume's
+/// contents from the host.
+pub async fn export_volume_to_host(volume_name: String, dest: PathBuf) -> Result<(), DockerError> {
+    let docker = super::pool::get().await;
+
+    create_save_backup_container(&docker, &volume_name).await?;
+
+    let result = run_volume_export(&docker, &dest).await;
+
+    let _ = docker
+        .remove_container(
+            SAVE_BACKUP_CONTAINER,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
         .await;
 
-    // // Start the exec instance and capture the output
-    // let mut exec_stream = docker.start_exec(&exec.id, None).await;
+    result
+}
+
+/// The reverse of `export_volume_to_host`: unpacks a `.tar` archive produced
+/// by it back into a named volume via bollard's copy-into-container endpoint.
+pub async fn import_host_to_volume(src: PathBuf, volume_name: String) -> Result<(), DockerError> {
+    let docker = super::pool::get().await;
+
+    create_save_backup_container(&docker, &volume_name).await?;
+
+    let result = run_volume_import(&docker, &src).await;
+
+    let _ = docker
+        .remove_container(
+            SAVE_BACKUP_CONTAINER,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
+        .await;
 
-    let mut contents = Vec::new();
-    Ok(contents)
+    result
+}
+
+async fn create_save_backup_container(docker: &Docker, volume_name: &str) -> Result<(), DockerError> {
+    let config = Config {
+        image: Some("alpine"),
+        host_config: Some(HostConfig {
+            binds: Some(vec![format!("{}:{}", volume_name, VOLUME_MOUNT_POINT)]),
+            ..Default::default()
+        }),
+        cmd: Some(vec!["sleep".to_string(), "300".to_string()]),
+        ..Default::default()
+    };
+
+    docker
+        .create_container(
+            Some(CreateContainerOptions {
+                name: SAVE_BACKUP_CONTAINER,
+                platform: Some("linux".to_string().as_str()),
+            }),
+            config,
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| DockerError {
+            message: format!("Error creating save backup container: {:?}", e),
+        })
+}
+
+async fn run_volume_export(docker: &Docker, dest: &PathBuf) -> Result<(), DockerError> {
+    docker
+        .start_container(
+            SAVE_BACKUP_CONTAINER,
+            None::<StartContainerOptions<String>>,
+        )
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error starting save backup container: {:?}", e),
+        })?;
+
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| DockerError {
+            message: format!("Error creating backup directory {:?}: {:?}", parent, e),
+        })?;
+    }
+
+    let mut file = tokio::fs::File::create(dest).await.map_err(|e| DockerError {
+        message: format!("Error creating archive file {:?}: {:?}", dest, e),
+    })?;
+
+    let mut stream = docker.download_from_container(
+        SAVE_BACKUP_CONTAINER,
+        Some(DownloadFromContainerOptions {
+            path: VOLUME_MOUNT_POINT.to_string(),
+        }),
+    );
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| DockerError {
+            message: format!("Error reading volume export stream: {:?}", e),
+        })?;
+        file.write_all(&chunk).await.map_err(|e| DockerError {
+            message: format!("Error writing archive chunk: {:?}", e),
+        })?;
+    }
+
+    Ok(())
+}
+
+async fn run_volume_import(docker: &Docker, src: &PathBuf) -> Result<(), DockerError> {
+    docker
+        .start_container(
+            SAVE_BACKUP_CONTAINER,
+            None::<StartContainerOptions<String>>,
+        )
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error starting save restore container: {:?}", e),
+        })?;
+
+    let tar_bytes = tokio::fs::read(src).await.map_err(|e| DockerError {
+        message: format!("Error reading archive {:?}: {:?}", src, e),
+    })?;
+
+    docker
+        .upload_to_container(
+            SAVE_BACKUP_CONTAINER,
+            Some(UploadToContainerOptions {
+                path: VOLUME_MOUNT_POINT.to_string(),
+                ..Default::default()
+            }),
+            tar_bytes.into(),
+        )
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error uploading archive into volume: {:?}", e),
+        })
+}
+
+/// Exports `volume_name` to a fresh timestamped archive under the game's
+/// `saves/` folder and records the backup in its manifest, so `slug`'s save
+/// history is versioned and portable alongside its scrape data.
+pub async fn backup_game_saves(
+    config: &Arc<MasterConfig>,
+    slug: &str,
+    volume_name: &str,
+) -> Result<PathBuf, DockerError> {
+    let archive_path = ManifestManager::saves_dir(config, slug)
+        .join(format!("{}.tar", Utc::now().timestamp()));
+
+    export_volume_to_host(volume_name.to_string(), archive_path.clone()).await?;
+
+    ManifestManager::record_save_backup(config, slug, archive_path.clone())
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Backup archive written but manifest update failed: {:?}", e),
+        })?;
+
+    Ok(archive_path)
 }
 
 /// Create a volume mount with the given name, driver, host path and labels
@@ -134,8 +426,8 @@ pub async fn create_volume_mount(
     driver: String,
     host_path: String,
     labels: HashMap<String, String>,
-) -> Result<(), bollard::errors::Error> {
-    let docker = Docker::connect_with_local_defaults().unwrap();
+) -> Result<(), DockerError> {
+    let docker = super::pool::get().await;
 
     let mut driver_opts = HashMap::new();
     driver_opts.insert("type".to_string(), "none".to_string());
@@ -149,15 +441,13 @@ pub async fn create_volume_mount(
         labels,
     };
 
-    match docker.create_volume(options).await {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e),
-    }
+    docker.create_volume(options).await.map(|_| ()).map_err(|e| DockerError {
+        message: format!("Error creating volume mount: {:?}", e),
+    })
 }
 
 pub async fn list_running_containers() -> Result<Vec<DockerContainer>, DockerError> {
-    let docker = Arc::clone(&DOCKER);
-    let docker = docker.lock().await;
+    let docker = super::pool::get().await;
 
     // Set up the filter for querying
     let mut list_container_filters = HashMap::new();
@@ -190,60 +480,398 @@ pub async fn list_running_containers() -> Result<Vec<DockerContainer>, DockerErr
     Ok(containers)
 }
 
+/// Turns `host:container` port mapping strings into the exposed-ports /
+/// port-bindings maps bollard's `Config`/`HostConfig` expect. Entries with no
+/// ':' are skipped; everything after the first ':' is taken as the container
+/// port.
+fn build_port_bindings(
+    ports: &[String],
+) -> (
+    HashMap<String, HashMap<(), ()>>,
+    HashMap<String, Option<Vec<PortBinding>>>,
+) {
+    let mut exposed_ports = HashMap::new();
+    let mut port_bindings = HashMap::new();
+
+    for mapping in ports {
+        let mut parts = mapping.splitn(2, ':');
+        let (host_port, container_port) = match (parts.next(), parts.next()) {
+            (Some(host), Some(container)) => (host, container),
+            _ => continue,
+        };
+
+        let key = format!("{}/tcp", container_port);
+        exposed_ports.insert(key.clone(), HashMap::new());
+        port_bindings.insert(
+            key,
+            Some(vec![PortBinding {
+                host_ip: None,
+                host_port: Some(host_port.to_string()),
+            }]),
+        );
+    }
+
+    (exposed_ports, port_bindings)
+}
+
+/// Creates and starts a container from `run_params`, wiring its memory/CPU
+/// caps and volume/port bindings into bollard's `Config`/`HostConfig` - the
+/// shape needed to actually boot a game/emulator image rather than a
+/// hardcoded alpine shell.
 pub async fn docker_run(run_params: DockerRunParams) -> Result<DockerContainer, DockerError> {
-    let docker = Arc::clone(&DOCKER);
-    let docker = docker.lock().await;
-    let mut volumes = HashMap::new();
-    volumes.insert("/tmp:/tmp".to_string(), HashMap::new());
+    let docker = super::pool::get().await;
+
+    let binds = run_params
+        .volumes
+        .clone()
+        .unwrap_or_else(|| vec!["/tmp:/tmp".to_string()]);
+    let (exposed_ports, port_bindings) =
+        build_port_bindings(run_params.ports.as_deref().unwrap_or(&[]));
 
-    let alpine_config = Config {
+    let config = Config {
         image: Some(run_params.image_name.clone()),
         tty: run_params.tty,
-        cmd: Some(vec!["/bin/sh".to_string()]),
-        volumes: Some(volumes),
+        cmd: Some(
+            run_params
+                .cmd
+                .clone()
+                .unwrap_or_else(|| vec!["/bin/sh".to_string()]),
+        ),
+        env: run_params.env_vars.clone(),
+        exposed_ports: Some(exposed_ports),
+        host_config: Some(HostConfig {
+            binds: Some(binds),
+            port_bindings: Some(port_bindings),
+            memory: run_params.memory.map(|m| m as i64),
+            nano_cpus: run_params.nano_cpus.map(|n| n as i64),
+            ..Default::default()
+        }),
         ..Default::default()
     };
 
     let id = docker
-        .create_container::<String, String>(None, alpine_config)
+        .create_container::<String, String>(None, config)
         .await
-        .unwrap()
+        .map_err(|e| DockerError {
+            message: format!("Error creating container: {:?}", e),
+        })?
         .id;
 
     match docker.start_container::<String>(&id, None).await {
         Ok(_) => {
-            docker
-                .create_exec(
-                    &id,
-                    CreateExecOptions {
-                        attach_stdout: Some(true),
-                        attach_stderr: Some(true),
-                        attach_stdin: Some(true),
-                        tty: Some(true),
-                        cmd: Some(vec!["/bin/sh"]),
-                        ..Default::default()
-                    },
-                )
-                .await
-                .unwrap()
-                .id;
-
-            let container = DockerContainer {
-                id: id.clone(),
+            tokio::spawn(docker_stats_stream(id.clone()));
+            Ok(DockerContainer {
+                id,
                 image: run_params.image_name.clone(),
-            };
+            })
+        }
+        Err(e) => Err(DockerError {
+            message: format!("Error starting container: {:?}", e),
+        }),
+    }
+}
+
+/// Starts a previously-created exec instance and collects everything it
+/// prints. Non-TTY execs are already framed by bollard into `StdOut`/`StdErr`
+/// `LogOutput` chunks, which are routed to the matching buffer; TTY execs
+/// arrive multiplexed on one stream and are passed through to stdout
+/// verbatim, since there's no way to split them after the fact.
+pub async fn docker_exec_stream(
+    container_id: &str,
+    cmd: Vec<&str>,
+    tty: bool,
+) -> Result<DockerExecResults, DockerError> {
+    let docker = super::pool::get().await;
+
+    let exec = docker
+        .create_exec(
+            container_id,
+            CreateExecOptions {
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                tty: Some(tty),
+                cmd: Some(cmd),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error creating exec on {}: {:?}", container_id, e),
+        })?;
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
 
-            Ok(container)
+    match docker
+        .start_exec(&exec.id, None)
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Error starting exec {}: {:?}", exec.id, e),
+        })? {
+        StartExecResults::Attached { mut output, .. } => {
+            while let Some(chunk) = output.next().await {
+                match chunk {
+                    Ok(LogOutput::StdOut { message }) => stdout.extend_from_slice(&message),
+                    Ok(LogOutput::StdErr { message }) => stderr.extend_from_slice(&message),
+                    Ok(LogOutput::Console { message }) => stdout.extend_from_slice(&message),
+                    Ok(LogOutput::StdIn { .. }) => {}
+                    Err(e) => {
+                        debug!("Exec stream for {} ended: {:?}", exec.id, e);
+                        break;
+                    }
+                }
+            }
         }
-        Err(e) => {
-            return Err(DockerError {
-                message: format!("Error starting container: {:?}", e),
-            })
+        StartExecResults::Detached => {
+            debug!("Exec {} started detached, no output to collect", exec.id);
         }
     }
+
+    let exit_code = docker
+        .inspect_exec(&exec.id)
+        .await
+        .ok()
+        .and_then(|inspect| inspect.exit_code);
+
+    Ok(DockerExecResults {
+        container_id: container_id.to_string(),
+        exec_id: exec.id,
+        stdout: String::from_utf8_lossy(&stdout).to_string(),
+        stderr: String::from_utf8_lossy(&stderr).to_string(),
+        exit_code,
+    })
+}
+
+/// Opens Docker's streaming `/containers/{id}/stats` endpoint and pushes a
+/// `ContainerStatsMessage` to every connected websocket client for each frame.
+/// The stream ends on its own once the container exits, which is also what
+/// stops this loop - there's nothing extra to cancel.
+pub async fn docker_stats_stream(container_id: String) {
+    let docker = super::pool::get().await;
+
+    let mut stats_stream = docker.stats(
+        &container_id,
+        Some(StatsOptions {
+            stream: true,
+            one_shot: false,
+        }),
+    );
+    drop(docker);
+
+    while let Some(stats) = stats_stream.next().await {
+        let stats = match stats {
+            Ok(stats) => stats,
+            Err(e) => {
+                debug!("Stats stream for {} ended: {:?}", container_id, e);
+                break;
+            }
+        };
+
+        let cpu_delta = stats.cpu_stats.cpu_usage.total_usage as f64
+            - stats.precpu_stats.cpu_usage.total_usage as f64;
+        let system_delta =
+            stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
+                - stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
+        let online_cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
+
+        let cpu_percent = if system_delta > 0.0 && cpu_delta > 0.0 {
+            (cpu_delta / system_delta) * online_cpus * 100.0
+        } else {
+            0.0
+        };
+
+        let (net_rx_bytes, net_tx_bytes) = stats.networks.unwrap_or_default().values().fold(
+            (0u64, 0u64),
+            |(rx, tx), network| (rx + network.rx_bytes, tx + network.tx_bytes),
+        );
+
+        let message = ContainerStatsMessage {
+            component: "container_stats".to_string(),
+            container_id: container_id.clone(),
+            cpu_percent,
+            mem_usage: stats.memory_stats.usage.unwrap_or(0),
+            mem_limit: stats.memory_stats.limit.unwrap_or(0),
+            net_rx_bytes,
+            net_tx_bytes,
+        };
+
+        let json_payload = serde_json::to_string(&message).unwrap();
+        socket_client::send_message(json_payload).await;
+    }
+
+    info!("Stats stream for container {} has ended", container_id);
+}
+
+/// Watches Docker's event stream for a single container's `start` and
+/// `die`/`stop` events and records a play session once it exits, so "last
+/// played" and total playtime stay accurate without polling the container.
+pub async fn track_play_session(container_id: String, slug: String, config: Arc<MasterConfig>) {
+    let docker = super::pool::get().await;
+
+    let mut filters = HashMap::new();
+    filters.insert("container".to_string(), vec![container_id.clone()]);
+    filters.insert(
+        "event".to_string(),
+        vec!["start".to_string(), "die".to_string(), "stop".to_string()],
+    );
+
+    let mut events = docker.events(Some(EventsOptions::<String> {
+        filters,
+        ..Default::default()
+    }));
+    drop(docker);
+
+    let mut started_at: Option<chrono::DateTime<Utc>> = None;
+
+    while let Some(event) = events.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                debug!("Event stream for {} ended: {:?}", container_id, e);
+                break;
+            }
+        };
+
+        match event.action.as_deref() {
+            Some("start") => {
+                started_at = Some(Utc::now());
+            }
+            Some("die") | Some("stop") => {
+                let start = match started_at {
+                    Some(start) => start,
+                    None => continue,
+                };
+                let duration = (Utc::now() - start).num_seconds().max(0) as u32;
+
+                let session = PlayStats {
+                    time_played: Some(start),
+                    duration: Some(duration),
+                };
+
+                if let Err(e) = ManifestManager::add_play_session(&config, &slug, session).await {
+                    debug!("Could not record play session for {}: {:?}", slug, e);
+                }
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    info!("Play session tracking for container {} has ended", container_id);
+}
+
+/// A single resource-usage reading for a running container, used to feed a
+/// `PlayStats` overlay for the active game session rather than the
+/// websocket broadcast that `docker_stats_stream` does.
+#[derive(Serialize, Debug, Clone)]
+pub struct LiveContainerStats {
+    pub cpu_pct: f64,
+    pub mem_bytes: u64,
+    pub mem_limit: u64,
+}
+
+/// Streams CPU/memory usage for a running container, invoking `on_stats` for
+/// each frame. The first frame has no previous values to diff against (its
+/// `precpu_stats` are zeroed), so it is skipped, as is any frame whose CPU or
+/// system delta is not positive. Ends once the container's stats stream ends.
+pub async fn container_stats_stream(
+    container_id: String,
+    mut on_stats: impl FnMut(LiveContainerStats),
+) {
+    let docker = super::pool::get().await;
+
+    let mut stats_stream = docker.stats(
+        &container_id,
+        Some(StatsOptions {
+            stream: true,
+            one_shot: false,
+        }),
+    );
+    drop(docker);
+
+    while let Some(stats) = stats_stream.next().await {
+        let stats = match stats {
+            Ok(stats) => stats,
+            Err(e) => {
+                debug!("Stats stream for {} ended: {:?}", container_id, e);
+                break;
+            }
+        };
+
+        let cpu_delta = stats.cpu_stats.cpu_usage.total_usage as f64
+            - stats.precpu_stats.cpu_usage.total_usage as f64;
+        let system_delta = stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
+            - stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
+        let online_cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
+        let first_frame = stats.precpu_stats.system_cpu_usage.unwrap_or(0) == 0;
+
+        if first_frame || system_delta <= 0.0 || cpu_delta <= 0.0 {
+            continue;
+        }
+
+        on_stats(LiveContainerStats {
+            cpu_pct: (cpu_delta / system_delta) * online_cpus * 100.0,
+            mem_bytes: stats.memory_stats.usage.unwrap_or(0),
+            mem_limit: stats.memory_stats.limit.unwrap_or(0),
+        });
+    }
+
+    info!("Live stats stream for container {} has ended", container_id);
 }
 
+/// Tails `container_id`'s combined stdout/stderr to `log_path`, keeping the
+/// file bounded to `limit_bytes` by dropping the oldest bytes once exceeded -
+/// the bounded game-log approach launcher projects use, so a chatty game
+/// can't fill disk while still leaving enough recent output to diagnose a
+/// launch failure. Runs until the container's log stream ends (i.e. the
+/// container exits), same lifecycle as `track_play_session`.
+pub async fn tail_container_log(container_id: &str, log_path: &PathBuf, limit_bytes: u64) {
+    if let Some(parent) = log_path.parent() {
+        if let Err(e) = tokio::fs::create_dir_all(parent).await {
+            debug!("Could not create game log directory {:?}: {:?}", parent, e);
+            return;
+        }
+    }
+
+    let docker = super::pool::get().await;
 
+    let mut log_stream = docker.logs(
+        container_id,
+        Some(LogsOptions::<String> {
+            follow: true,
+            stdout: true,
+            stderr: true,
+            ..Default::default()
+        }),
+    );
+    drop(docker);
+
+    let mut buffer: Vec<u8> = Vec::new();
+
+    while let Some(chunk) = log_stream.next().await {
+        let message = match chunk {
+            Ok(LogOutput::StdOut { message }) | Ok(LogOutput::StdErr { message }) => message,
+            Ok(LogOutput::Console { message }) => message,
+            Ok(LogOutput::StdIn { .. }) => continue,
+            Err(e) => {
+                debug!("Log stream for {} ended: {:?}", container_id, e);
+                break;
+            }
+        };
+
+        buffer.extend_from_slice(&message);
+        let overflow = buffer.len().saturating_sub(limit_bytes as usize);
+        if overflow > 0 {
+            buffer.drain(0..overflow);
+        }
+
+        if let Err(e) = tokio::fs::write(log_path, &buffer).await {
+            debug!("Could not write game log {:?}: {:?}", log_path, e);
+        }
+    }
+
+    info!("Log capture for container {} has ended", container_id);
+}
 
 #[derive(Serialize)]
 pub enum UpStatus {
@@ -283,7 +911,10 @@ pub struct DockerError {
 #[derive(Debug, Serialize)]
 pub struct DockerExecResults {
     pub container_id: String,
-    pub container_name: String,
+    pub exec_id: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i64>,
 }
 
 #[derive(Serialize, Clone, Debug)]
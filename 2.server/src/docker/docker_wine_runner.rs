@@ -1,13 +1,140 @@
-use crate::{config, handlers::docker_interactor::DockerError, helpers::docker_run_params::DockerRunParams};
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+use wincompatlib::prelude::*;
 
+use crate::{
+    config::{self, MasterConfig},
+    helpers::docker_run_params::DockerRunParams,
+};
 
-pub async fn build_run_params() -> Result<DockerRunParams, DockerError> {
+use super::docker_interactor::DockerError;
 
-    let t = config::WINE_RUNNER_NAME;
-    // Create an empty DockerRunParams object
-    let mut run_params = DockerRunParams::new(config::WINE_RUNNER_NAME.to_string());
+/// Per-install Wine/DXVK version pins, configurable via `MasterConfig` so
+/// different games can target different toolchains without code changes -
+/// the same wine-prefix + DXVK management approach anime/honkers launchers
+/// use (see `wincompatlib`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunnerConfig {
+    #[serde(default = "default_wine_version")]
+    pub wine_version: String,
+    #[serde(default)]
+    pub dxvk_version: Option<String>,
+    /// Maximum size, in bytes, a launched game's `game.log` is allowed to
+    /// grow to before the oldest output is dropped to make room. Falls back
+    /// to the `LAUNCHER_GAME_LOG_FILE_LIMIT` env var, then
+    /// `DEFAULT_GAME_LOG_LIMIT`, when unset.
+    #[serde(default)]
+    pub log_limit: Option<u64>,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        RunnerConfig {
+            wine_version: default_wine_version(),
+            dxvk_version: None,
+            log_limit: None,
+        }
+    }
+}
+
+fn default_wine_version() -> String {
+    "staging".to_string()
+}
+
+/// Env var consulted when `runner.log_limit` isn't set in the master config.
+const GAME_LOG_FILE_LIMIT_ENV: &str = "LAUNCHER_GAME_LOG_FILE_LIMIT";
+
+/// Used when neither `runner.log_limit` nor `LAUNCHER_GAME_LOG_FILE_LIMIT`
+/// are set.
+const DEFAULT_GAME_LOG_LIMIT: u64 = 2 * 1024 * 1024;
+
+fn game_log_limit(config: &MasterConfig) -> u64 {
+    config
+        .runner
+        .log_limit
+        .or_else(|| env::var(GAME_LOG_FILE_LIMIT_ENV).ok().and_then(|v| v.parse().ok()))
+        .unwrap_or(DEFAULT_GAME_LOG_LIMIT)
+}
+
+/// Where a game's captured console output lives: `{root_dir}/{slug}/game.log`.
+fn game_log_path(config: &MasterConfig, slug: &str) -> PathBuf {
+    config.root_dir.join(slug).join("game.log")
+}
+
+/// Tees `container_id`'s combined stdout/stderr to `{slug}/game.log`, bounded
+/// to `runner.log_limit` (or `LAUNCHER_GAME_LOG_FILE_LIMIT`/
+/// `DEFAULT_GAME_LOG_LIMIT`) bytes - meant to be spawned right after
+/// `docker_run` starts the container built from `build_run_params`, the same
+/// way `docker_interactor::track_play_session` is spawned alongside a launch.
+pub async fn capture_game_log(config: &Arc<MasterConfig>, slug: &str, container_id: &str) {
+    let path = game_log_path(config, slug);
+    let limit = game_log_limit(config);
+    super::docker_interactor::tail_container_log(container_id, &path, limit).await;
+}
+
+/// Where a game's Wine prefix lives: `{root_dir}/{slug}/wineprefix`.
+fn prefix_path(config: &MasterConfig, slug: &str) -> PathBuf {
+    config.root_dir.join(slug).join("wineprefix")
+}
+
+/// Where a downloaded DXVK build is cached once fetched:
+/// `{root_dir}/{slug}/wineprefix/dxvk/{version}`.
+fn dxvk_path(config: &MasterConfig, slug: &str, version: &str) -> PathBuf {
+    prefix_path(config, slug).join("dxvk").join(version)
+}
+
+/// Builds the `DockerRunParams` needed to launch `executable` under Wine for
+/// `slug`: creates (or reuses) a per-game Wine prefix, applies the configured
+/// DXVK build into it if one is cached, and points the container at the
+/// prefix mount and the target executable.
+pub async fn build_run_params(
+    config: &Arc<MasterConfig>,
+    slug: &str,
+    executable: &str,
+) -> Result<DockerRunParams, DockerError> {
+    let prefix = prefix_path(config, slug);
+    tokio::fs::create_dir_all(&prefix)
+        .await
+        .map_err(|e| DockerError {
+            message: format!("Could not create wine prefix directory {:?}: {:?}", prefix, e),
+        })?;
+
+    let wine = Wine::from_binary(&config.runner.wine_version).with_prefix(prefix.clone());
+
+    wine.update_prefix(None::<PathBuf>).map_err(|e| DockerError {
+        message: format!("Could not initialize wine prefix {:?}: {:?}", prefix, e),
+    })?;
+
+    if let Some(dxvk_version) = &config.runner.dxvk_version {
+        let dxvk_build = dxvk_path(config, slug, dxvk_version);
+        if dxvk_build.exists() {
+            Dxvk::install(&wine, &dxvk_build, InstallParams::default()).map_err(|e| DockerError {
+                message: format!("Could not install DXVK {} into prefix: {:?}", dxvk_version, e),
+            })?;
+        }
+    }
+
+    let mut env = HashMap::new();
+    env.insert("WINEPREFIX".to_string(), "/root/.wine".to_string());
+    if config.runner.dxvk_version.is_some() {
+        env.insert(
+            "WINEDLLOVERRIDES".to_string(),
+            "d3d11,d3d10core,d3d9,dxgi=n".to_string(),
+        );
+    }
+
+    let run_params = DockerRunParams::new(config::WINE_RUNNER_NAME.to_string())
+        .volumes(vec![format!(
+            "{}:{}",
+            prefix.to_string_lossy(),
+            "/root/.wine"
+        )])
+        .env(env)
+        .cmd(vec!["wine".to_string(), executable.to_string()]);
 
-    // run_params.
     Ok(run_params)
-}
\ No newline at end of file
+}
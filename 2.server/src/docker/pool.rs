@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use bb8::{Pool, PooledConnection};
+use bollard::{Docker, API_DEFAULT_VERSION};
+use log::debug;
+use serde::Serialize;
+use tokio::sync::OnceCell;
+
+use crate::config::MasterConfig;
+
+/// Falls back to a rootless podman socket under the *current* user's runtime
+/// directory - rather than a uid hardcoded to 1000 - when nothing more
+/// specific is configured. The uid comes from `UID`, else from the owner of
+/// `/proc/self`; 1000 is used only when neither is available.
+fn default_socket() -> String {
+    use std::os::unix::fs::MetadataExt;
+    let uid = std::env::var("UID")
+        .ok()
+        .or_else(|| std::fs::metadata("/proc/self").ok().map(|meta| meta.uid().to_string()))
+        .unwrap_or_else(|| "1000".to_string());
+    format!("unix:///run/user/{}/podman/podman.sock", uid)
+}
+
+/// Picks the container daemon address to connect to, in priority order:
+/// `docker_host` in the master config, then the `DOCKER_HOST`/`CONTAINER_HOST`
+/// env vars (as honored by the official docker/podman clients), then a
+/// rootless podman socket as a last resort.
+fn resolve_host(config: &MasterConfig) -> String {
+    config
+        .docker_host
+        .clone()
+        .or_else(|| std::env::var("DOCKER_HOST").ok())
+        .or_else(|| std::env::var("CONTAINER_HOST").ok())
+        .unwrap_or_else(default_socket)
+}
+
+/// Opens a connection to whichever transport `host` names - `unix://` for a
+/// local socket, `tcp://`/`http://` for a remote daemon.
+fn connect(host: &str) -> Result<Docker, bollard::errors::Error> {
+    if host.starts_with("tcp://") || host.starts_with("http://") {
+        Docker::connect_with_http(host, 120, API_DEFAULT_VERSION)
+    } else {
+        Docker::connect_with_unix(host, 120, API_DEFAULT_VERSION)
+    }
+}
+
+/// Tells `bb8` how to open, validate and recycle connections to the
+/// container daemon, so handlers can borrow a connection instead of opening
+/// a fresh socket per request.
+#[derive(Debug, Clone)]
+pub struct DockerConnectionManager {
+    host: String,
+}
+
+#[async_trait]
+impl bb8::ManageConnection for DockerConnectionManager {
+    type Connection = Docker;
+    type Error = bollard::errors::Error;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        debug!("Opening a new pooled docker daemon connection to {}", self.host);
+        connect(&self.host)
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        conn.ping().await.map(|_| ())
+    }
+
+    fn has_broken(&self, _conn: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
+pub type DockerPool = Pool<DockerConnectionManager>;
+pub type PooledDocker = PooledConnection<'static, DockerConnectionManager>;
+
+static DOCKER_POOL: OnceCell<DockerPool> = OnceCell::const_new();
+
+async fn build_pool(config: &MasterConfig) -> DockerPool {
+    let manager = DockerConnectionManager {
+        host: resolve_host(config),
+    };
+
+    Pool::builder()
+        .max_size(config.docker_pool_size)
+        .build(manager)
+        .await
+        .unwrap_or_else(|e| panic!("Could not build docker connection pool: {:?}", e))
+}
+
+/// Returns the shared docker connection pool, building it from `GLOBAL_CONFIG`
+/// on first use.
+pub async fn pool() -> &'static DockerPool {
+    DOCKER_POOL
+        .get_or_init(|| async { build_pool(&crate::GLOBAL_CONFIG.load()).await })
+        .await
+}
+
+/// Borrows a pooled docker daemon connection. Callers use it exactly like a
+/// `Docker` handle - it derefs straight through.
+pub async fn get() -> PooledDocker {
+    pool()
+        .await
+        .get_owned()
+        .await
+        .unwrap_or_else(|e| panic!("Could not check out a docker connection: {:?}", e))
+}
+
+#[derive(Serialize)]
+pub struct DockerPoolStatus {
+    pub idle_connections: u32,
+    pub active_connections: u32,
+    pub max_size: u32,
+}
+
+/// Reports idle/active counts for the `diag/docker_pool` route, so operators
+/// can size `docker_pool_size` in the master config.
+pub async fn status() -> DockerPoolStatus {
+    let pool = pool().await;
+    let state = pool.state();
+    DockerPoolStatus {
+        idle_connections: state.idle_connections,
+        active_connections: state.connections - state.idle_connections,
+        max_size: pool.max_size(),
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::docker_interactor::{
+    self, DockerContainer, DockerError, DockerExecResults, DockerStatus,
+};
+use crate::helpers::docker_run_params::DockerRunParams;
+
+/// Abstracts away the container backend so callers aren't tied to a specific
+/// daemon connection or transport. Lets tests substitute a mock runtime
+/// instead of requiring a live docker/podman daemon.
+#[async_trait]
+pub trait ContainerRuntime: Send + Sync {
+    async fn get_daemon_status(&self) -> DockerStatus;
+
+    async fn list_named_volumes(&self) -> Result<Vec<String>, DockerError>;
+
+    async fn create_volume_mount(
+        &self,
+        name: String,
+        driver: String,
+        host_path: String,
+        labels: HashMap<String, String>,
+    ) -> Result<(), DockerError>;
+
+    async fn list_running_containers(&self) -> Result<Vec<DockerContainer>, DockerError>;
+
+    async fn docker_run(&self, run_params: DockerRunParams) -> Result<DockerContainer, DockerError>;
+
+    async fn exec(
+        &self,
+        container_id: &str,
+        cmd: Vec<&str>,
+        tty: bool,
+    ) -> Result<DockerExecResults, DockerError>;
+}
+
+/// The production `ContainerRuntime`, backed by the pooled bollard
+/// connection built from `MasterConfig::docker_host` (see `docker::pool`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BollardRuntime;
+
+#[async_trait]
+impl ContainerRuntime for BollardRuntime {
+    async fn get_daemon_status(&self) -> DockerStatus {
+        docker_interactor::get_docker_daemon_status().await
+    }
+
+    async fn list_named_volumes(&self) -> Result<Vec<String>, DockerError> {
+        let volumes = docker_interactor::list_named_volumes().await?;
+        Ok(volumes.into_iter().map(|v| v.name).collect())
+    }
+
+    async fn create_volume_mount(
+        &self,
+        name: String,
+        driver: String,
+        host_path: String,
+        labels: HashMap<String, String>,
+    ) -> Result<(), DockerError> {
+        docker_interactor::create_volume_mount(name, driver, host_path, labels).await
+    }
+
+    async fn list_running_containers(&self) -> Result<Vec<DockerContainer>, DockerError> {
+        docker_interactor::list_running_containers().await
+    }
+
+    async fn docker_run(&self, run_params: DockerRunParams) -> Result<DockerContainer, DockerError> {
+        docker_interactor::docker_run(run_params).await
+    }
+
+    async fn exec(
+        &self,
+        container_id: &str,
+        cmd: Vec<&str>,
+        tty: bool,
+    ) -> Result<DockerExecResults, DockerError> {
+        docker_interactor::docker_exec_stream(container_id, cmd, tty).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `ContainerRuntime` that never touches a daemon, so handler-level
+    /// tests can exercise the docker routes without docker/podman installed.
+    struct MockRuntime;
+
+    #[async_trait]
+    impl ContainerRuntime for MockRuntime {
+        async fn get_daemon_status(&self) -> DockerStatus {
+            DockerStatus {
+                up_status: docker_interactor::UpStatus::Up,
+            }
+        }
+
+        async fn list_named_volumes(&self) -> Result<Vec<String>, DockerError> {
+            Ok(vec!["dillinger_main".to_string()])
+        }
+
+        async fn create_volume_mount(
+            &self,
+            _name: String,
+            _driver: String,
+            _host_path: String,
+            _labels: HashMap<String, String>,
+        ) -> Result<(), DockerError> {
+            Ok(())
+        }
+
+        async fn list_running_containers(&self) -> Result<Vec<DockerContainer>, DockerError> {
+            Ok(vec![DockerContainer {
+                id: "abc123".to_string(),
+                image: "alpine:latest".to_string(),
+            }])
+        }
+
+        async fn docker_run(
+            &self,
+            run_params: DockerRunParams,
+        ) -> Result<DockerContainer, DockerError> {
+            Ok(DockerContainer {
+                id: "mock-container".to_string(),
+                image: run_params.image_name,
+            })
+        }
+
+        async fn exec(
+            &self,
+            container_id: &str,
+            _cmd: Vec<&str>,
+            _tty: bool,
+        ) -> Result<DockerExecResults, DockerError> {
+            Ok(DockerExecResults {
+                container_id: container_id.to_string(),
+                exec_id: "mock-exec".to_string(),
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: Some(0),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_runtime_satisfies_container_runtime() {
+        let runtime: Box<dyn ContainerRuntime> = Box::new(MockRuntime);
+
+        let status = runtime.get_daemon_status().await;
+        assert!(matches!(status.up_status, docker_interactor::UpStatus::Up));
+
+        let volumes = runtime.list_named_volumes().await.unwrap();
+        assert_eq!(volumes, vec!["dillinger_main".to_string()]);
+
+        let containers = runtime.list_running_containers().await.unwrap();
+        assert_eq!(containers.len(), 1);
+
+        let run_params = DockerRunParams::new("alpine:latest".to_string()).build();
+        let container = runtime.docker_run(run_params).await.unwrap();
+        assert_eq!(container.image, "alpine:latest");
+    }
+}
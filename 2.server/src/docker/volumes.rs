@@ -1,4 +1,5 @@
 use serde::Serialize;
+use std::collections::HashMap;
 use std::{fs::File, path::Component};
 
 #[derive(Clone, Serialize, Debug)]
@@ -20,6 +21,9 @@ impl DockerVolume {
 pub struct VolumeContents {
     pub folders: Vec<String>,
     pub files: Vec<String>,
+    // Only populated by a stat-style listing - maps a name in `files` to its
+    // size in bytes, so the UI can show per-save-file sizes.
+    pub file_sizes: Option<HashMap<String, u64>>,
 }
 
 impl VolumeContents {
@@ -27,6 +31,7 @@ impl VolumeContents {
         VolumeContents {
             folders: Vec::new(),
             files: Vec::new(),
+            file_sizes: None,
         }
     }
 }
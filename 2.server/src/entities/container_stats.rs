@@ -0,0 +1,24 @@
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+pub struct ContainerStatsMessage {
+    pub component: String,
+    pub container_id: String,
+    pub cpu_percent: f64,
+    pub mem_usage: u64,
+    pub mem_limit: u64,
+    pub net_rx_bytes: u64,
+    pub net_tx_bytes: u64,
+}
+
+impl ContainerStatsMessage {
+    pub fn new(container_id: String) -> Self {
+        Self {
+            component: "container_stats".to_string(),
+            container_id,
+            cpu_percent: 0.0,
+            mem_usage: 0,
+            mem_limit: 0,
+            net_rx_bytes: 0,
+            net_tx_bytes: 0,
+        }
+    }
+}
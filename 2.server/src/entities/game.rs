@@ -2,11 +2,13 @@ use std::fmt::Error;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 use crate::platform::Platform;
 
 use super::playstats::PlayStats;
+use super::save_backup::SaveBackup;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, ToSchema)]
 pub struct Game {
     pub slug: String,
     pub name: String,
@@ -14,6 +16,9 @@ pub struct Game {
     pub summary: String,
     pub storyline: Option<String>,
     pub play_stats: Option<Vec<PlayStats>>,
+    #[schema(value_type = Option<String>)]
+    pub last_played: Option<chrono::DateTime<chrono::Utc>>,
+    pub save_backups: Option<Vec<SaveBackup>>,
     pub covers: Option<Vec<String>>,
     pub genres: Option<Vec<String>>,
     pub themes: Option<Vec<String>>,
@@ -34,6 +39,8 @@ impl Game {
             for_platform: Platform::default(), 
             storyline: None, 
             play_stats: None,
+            last_played: None,
+            save_backups: None,
             covers: None,
             genres: None,
             themes: None,
@@ -0,0 +1,48 @@
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+pub struct InstallStatus {
+    pub component: String,
+    pub label: String,
+    pub progress: u8,
+    pub complete: bool,
+    pub log_line: String,
+    pub error: Option<String>,
+    pub prompt_items: Option<Vec<String>>,
+}
+
+impl InstallStatus {
+    pub fn new(label: &str, progress: u8, log_line: &str) -> Self {
+        InstallStatus {
+            component: "lutris_install".to_string(),
+            label: label.to_string(),
+            progress,
+            complete: false,
+            log_line: log_line.to_string(),
+            error: None,
+            prompt_items: None,
+        }
+    }
+
+    pub fn failed(label: &str, error: String) -> Self {
+        InstallStatus {
+            component: "lutris_install".to_string(),
+            label: label.to_string(),
+            progress: 0,
+            complete: false,
+            log_line: format!("Step failed: {}", error),
+            error: Some(error),
+            prompt_items: None,
+        }
+    }
+
+    pub fn finished(label: &str) -> Self {
+        InstallStatus {
+            component: "lutris_install".to_string(),
+            label: label.to_string(),
+            progress: 100,
+            complete: true,
+            log_line: "Install complete".to_string(),
+            error: None,
+            prompt_items: None,
+        }
+    }
+}
@@ -3,8 +3,9 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, utoipa::ToSchema)]
 pub struct PlayStats {
+    #[schema(value_type = Option<String>)]
     pub time_played: Option<chrono::DateTime<chrono::Utc>>,
     pub duration: Option<u32>
 }
\ No newline at end of file
@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+/// One recorded save-game backup for a `Game` - where the archive landed on
+/// disk and when it was taken, so the UI can list and restore prior backups.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, utoipa::ToSchema)]
+pub struct SaveBackup {
+    #[schema(value_type = String)]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = String)]
+    pub archive_path: PathBuf,
+}
@@ -1,9 +1,10 @@
 use std::fmt::Error;
 
 use serde::Serialize;
+use utoipa::ToSchema;
 use crate::docker::docker_interactor::DockerError;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, ToSchema)]
 pub struct ErrorResponse {
     message: String,
 }
@@ -1,6 +1,6 @@
 use std::convert::Infallible;
 use log::info;
-use crate::handlers::docker_interactor::{self};
+use crate::docker::docker_interactor::{self};
 use crate::helpers::docker_run_params::DockerRunParams;
 
 #[derive(serde::Serialize, serde::Deserialize)]
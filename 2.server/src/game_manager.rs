@@ -8,7 +8,7 @@ use crate::{
 use std::{error::Error, path::PathBuf, sync::Arc};
 
 /// Represents a single entry in the game cache.
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct PlatformEntry {
     pub name: String,
     pub rom_files: Option<Vec<PathBuf>>,
@@ -17,7 +17,7 @@ pub struct PlatformEntry {
 }
 
 /// Represents a single entry in the game cache.
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct GameCacheEntry {
     pub slug: String,
     pub title: String,
@@ -17,7 +17,7 @@ pub trait GameDb : Send  {
     async fn get_screenshots(&mut self, id: u64, screenshot_info: Vec<String>) -> u32;
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, utoipa::ToSchema)]
 pub struct GameDbGameEntry {
     pub game_db: String,
     pub slug_game: String,
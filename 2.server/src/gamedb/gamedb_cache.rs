@@ -0,0 +1,122 @@
+use log::{debug, info};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::gamedbtoken::GameDbToken;
+
+const SLED_PATH: &str = "gamedb_cache.sled";
+
+static SLED_DB: OnceLock<sled::Db> = OnceLock::new();
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CacheEntry<T> {
+    inserted_at: u64,
+    value: T,
+}
+
+fn db() -> &'static sled::Db {
+    SLED_DB.get_or_init(|| {
+        sled::open(SLED_PATH).unwrap_or_else(|e| {
+            panic!("Could not open gamedb cache at {}: {:?}", SLED_PATH, e)
+        })
+    })
+}
+
+fn normalize_key(db_name: &str, method: &str, query: &str) -> String {
+    format!("{}:{}:{}", db_name, method, query.trim().to_lowercase())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+/// Fetches a cached response for `method`/`query` from the given entity tree,
+/// returning `None` if there's no entry or it's older than `ttl_secs` (see
+/// `MasterConfig::gamedb_cache_ttl_secs`).
+pub fn get_cached<T: DeserializeOwned>(
+    tree_name: &str,
+    db_name: &str,
+    method: &str,
+    query: &str,
+    ttl_secs: u64,
+) -> Option<T> {
+    let tree = db().open_tree(tree_name).ok()?;
+    let key = normalize_key(db_name, method, query);
+    let raw = tree.get(key.as_bytes()).ok().flatten()?;
+
+    let entry: CacheEntry<T> = bincode::deserialize(&raw).ok()?;
+    if now_secs().saturating_sub(entry.inserted_at) > ttl_secs {
+        debug!("Cache entry for {} expired", key);
+        return None;
+    }
+
+    debug!("Cache hit for {}", key);
+    Some(entry.value)
+}
+
+/// Persists a response for `method`/`query` in the given entity tree with an
+/// insertion timestamp, so future lookups can honor the TTL.
+pub fn put_cached<T: Serialize>(tree_name: &str, db_name: &str, method: &str, query: &str, value: &T) {
+    let Ok(tree) = db().open_tree(tree_name) else {
+        return;
+    };
+    let key = normalize_key(db_name, method, query);
+
+    let entry = CacheEntry {
+        inserted_at: now_secs(),
+        value,
+    };
+    if let Ok(encoded) = bincode::serialize(&entry) {
+        let _ = tree.insert(key.as_bytes(), encoded);
+    }
+}
+
+/// How much earlier than its advertised `expires_in` a cached token is
+/// treated as expired, so a call in flight doesn't get a 401 right as the
+/// clock runs out.
+const TOKEN_EXPIRY_SAFETY_MARGIN_SECS: u64 = 60;
+
+/// Loads a previously persisted auth token for `db_name`, returning `None` if
+/// there isn't one or it has expired.
+pub fn get_cached_token(db_name: &str) -> Option<GameDbToken> {
+    let tree = db().open_tree("tokens").ok()?;
+    let raw = tree.get(db_name.as_bytes()).ok().flatten()?;
+    let entry: CacheEntry<GameDbToken> = bincode::deserialize(&raw).ok()?;
+
+    let expires_in = entry.value.expires_in.unwrap_or(0);
+    if now_secs().saturating_sub(entry.inserted_at) + TOKEN_EXPIRY_SAFETY_MARGIN_SECS >= expires_in {
+        info!("Cached token for {} has expired", db_name);
+        return None;
+    }
+
+    Some(entry.value)
+}
+
+/// Persists a freshly authenticated token with its expiry, so `authenticate()`
+/// can be skipped on subsequent calls while it's still valid.
+pub fn put_cached_token(db_name: &str, token: &GameDbToken) {
+    let Ok(tree) = db().open_tree("tokens") else {
+        return;
+    };
+
+    let entry = CacheEntry {
+        inserted_at: now_secs(),
+        value: token,
+    };
+    if let Ok(encoded) = bincode::serialize(&entry) {
+        let _ = tree.insert(db_name.as_bytes(), encoded);
+    }
+}
+
+/// Discards a cached token, e.g. after the remote API rejects it as expired
+/// despite still being within our locally cached expiry window.
+pub fn invalidate_cached_token(db_name: &str) {
+    let Ok(tree) = db().open_tree("tokens") else {
+        return;
+    };
+    let _ = tree.remove(db_name.as_bytes());
+}
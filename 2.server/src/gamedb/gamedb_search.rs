@@ -1,58 +1,171 @@
-use bollard::secret::PluginConfigInterfaceProtocolSchemeEnum;
+use std::collections::HashMap;
+
+use chrono::Datelike;
+use futures::future::join_all;
 use log::info;
 
 use super::gamedb::GameDbGameEntry;
+use super::registry;
+use super::scrape_queue;
 use crate::{
+    config,
     entities::{dillinger_error::DillingerError, game::Game},
-    gamedb::{gamedb::GameDb, igdb::Igdb}, handlers::cache,
+    handlers::cache,
 };
 
+/// Sentinel `search_db` value that fans a search out across every enabled
+/// provider in `MasterConfig::game_dbs`, instead of querying just one - see
+/// `search_all_providers`.
+pub const ALL_PROVIDERS: &str = "all";
+
+/// Searches `search_db` for `search_term`, serving a cached result (up to
+/// `cache::DEFAULT_SEARCH_CACHE_TTL_SECS` old) unless `refresh` is set. A
+/// cancelled scrape that never gets past the confirm prompt no longer costs
+/// another live API call on the next attempt.
 pub async fn search_title(
     search_db: String,
-    search_term: String
+    search_term: String,
+    refresh: bool,
 ) -> Result<Vec<GameDbGameEntry>, DillingerError> {
     info!("route requested: search_title");
     info!("search db: {}", search_db);
 
-    // Based on the db name, construct the appropriate gamedb object
-    let mut db: Box<dyn GameDb> = match search_db.as_str() {
-        "igdb" => Box::new(Igdb::new()),
-        _ => {
-            return Err(DillingerError {
-                description: "Invalid search database".to_string(),
-            })
+    if !refresh {
+        if let Some(cached) = cache::read_search_cache::<Vec<GameDbGameEntry>>(
+            "search",
+            &search_db,
+            &search_term,
+            cache::DEFAULT_SEARCH_CACHE_TTL_SECS,
+        ) {
+            info!("Serving search_title for '{}' from cache", search_term);
+            return Ok(cached);
         }
+    }
+
+    let results = if search_db == ALL_PROVIDERS {
+        search_providers(&search_term, None).await?
+    } else if search_db.contains(',') {
+        let names: Vec<String> = search_db
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        search_providers(&search_term, Some(&names)).await?
+    } else {
+        let master_config = config::get_master_config();
+        let mut db = registry::build_named(&master_config.game_dbs, &search_db).ok_or_else(|| {
+            DillingerError {
+                description: "Invalid search database".to_string(),
+            }
+        })?;
+        db.search_game(&search_term).await
     };
 
-    // Search for matching titles
-    let results = db.search_game(&search_term).await;
+    cache::write_search_cache("search", &search_db, &search_term, &results);
 
     Ok(results)
 }
 
+/// Queries `names` (or, if `None`, every enabled `game_dbs` provider)
+/// concurrently and merges the results. Dedups by title + release year
+/// rather than `(game_db, slug_game)`, since two different backends assign
+/// the same game two different slugs - so fanning a search out across e.g.
+/// `"igdb,mobygames"` gives one merged hit per game instead of one per
+/// provider. Each merged hit's full details are queued on `scrape_queue` so
+/// this bulk, multi-provider search warms the `get_game_data` cache in the
+/// background instead of every result costing a live API call the moment a
+/// user picks one.
+async fn search_providers(
+    search_term: &str,
+    names: Option<&[String]>,
+) -> Result<Vec<GameDbGameEntry>, DillingerError> {
+    let master_config = config::get_master_config();
+    let mut dbs = match names {
+        Some(names) => names
+            .iter()
+            .filter_map(|name| registry::build_named(&master_config.game_dbs, name))
+            .collect::<Vec<_>>(),
+        None => registry::build_enabled(&master_config.game_dbs),
+    };
+    if dbs.is_empty() {
+        return Err(DillingerError {
+            description: "No game_dbs providers are enabled".to_string(),
+        });
+    }
+
+    let searches: Vec<_> = dbs.iter_mut().map(|db| db.search_game(search_term)).collect();
+    let per_provider_results = join_all(searches).await;
+
+    let mut seen: HashMap<String, Vec<Option<i32>>> = HashMap::new();
+    let mut merged = vec![];
+    for results in per_provider_results {
+        for entry in results {
+            let (title, year) = dedup_key(&entry);
+            // A release_date of 0 means the provider reported none; it matches any year.
+            let year = if entry.release_date == 0 { None } else { year };
+            let years = seen.entry(title).or_default();
+            if years.iter().any(|seen_year| seen_year.is_none() || year.is_none() || *seen_year == year) {
+                continue;
+            }
+            years.push(year);
+            scrape_queue::enqueue(&entry.game_db, &entry.slug_game).await;
+            merged.push(entry);
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Normalizes a title for cross-provider dedup comparison.
+fn normalize_title(title: &str) -> String {
+    title.trim().to_lowercase()
+}
+
+/// Cross-provider dedup key: title alone collapses distinct games that
+/// happen to share a name (annual sports titles, "Doom" 1993 vs 2016), so
+/// pair it with the release year as a secondary signal - two providers'
+/// entries only merge if they also agree on when the game came out.
+/// `search_providers` treats an entry with no release date (`release_date`
+/// 0) as matching any year for the same title.
+fn dedup_key(entry: &GameDbGameEntry) -> (String, Option<i32>) {
+    let year = chrono::DateTime::from_timestamp(entry.release_date as i64, 0).map(|dt| dt.year());
+    (normalize_title(&entry.name), year)
+}
+
+/// Fetches full game details for `game_slug` from `search_db`, serving a
+/// cached result unless `refresh` is set.
 pub async fn get_game_details(
     search_db: String,
     game_slug: String,
+    refresh: bool,
 ) -> Result<Game, DillingerError> {
     info!("route requested: get_game_details");
     info!("search db: {}", search_db);
     info!("search game_slug: {}", game_slug);
 
-    // Based on the db name, construct the appropriate gamedb object
-    let mut db: Box<dyn GameDb> = match search_db.as_str() {
-        "igdb" => Box::new(Igdb::new()),
-        _ => {
-            return Err(DillingerError {
-                description: "Invalid search database".to_string(),
-            })
+    if !refresh {
+        if let Some(cached) = cache::read_search_cache::<Game>(
+            "details",
+            &search_db,
+            &game_slug,
+            cache::DEFAULT_SEARCH_CACHE_TTL_SECS,
+        ) {
+            info!("Serving get_game_details for '{}' from cache", game_slug);
+            return Ok(cached);
         }
-    };
+    }
+
+    let master_config = config::get_master_config();
+    let mut db = registry::build_named(&master_config.game_dbs, &search_db).ok_or_else(|| DillingerError {
+        description: "Invalid search database".to_string(),
+    })?;
 
     // Search for matching titles
     match db.get_game_data(game_slug).await {
         Some(results) => {
             // Store the last result in the cache
-            cache::write_cache_last_search(results.clone());
+            cache::write_cache_last_search(results.clone()).await;
+            cache::write_search_cache("details", &search_db, &game_slug, &results);
             Ok(results)
         },
         None => {
@@ -2,27 +2,90 @@ use std::collections::HashMap;
 use std::f32::consts::E;
 
 use super::gamedb::{GameDb, GameDbGameEntry};
+use super::gamedb_cache;
 use super::gamedbtoken::GameDbToken;
+use super::registry::GameDbProviderConfig;
+use super::screenshot_queue;
+use crate::config;
 use crate::entities::dillinger_error::DillingerError;
 use crate::entities::game::{self, Game};
-use crate::handlers::web_request::{self, post};
+use crate::handlers::web_request::{self, post, WebError};
 use crate::platform::Platform;
 use async_trait::async_trait;
-use log::{error, info};
+use log::{error, info, warn};
+
+const SEARCH_TREE: &str = "igdb_search";
+const GAME_DATA_TREE: &str = "igdb_game_data";
 
-const IGDB_NAME: &str = "igdb";
-const IGDB_URL: &str = "https://api.igdb.com/v4/games";
 const IGDB_URL_PLATFORMS: &str = "https://api.igdb.com/v4/platforms";
 const IGDB_URL_SCREENSHOTS: &str = "https://api.igdb.com/v4/screenshots";
 
 pub struct Igdb {
     pub token: GameDbToken,
+    /// This provider's configured name - tags every `GameDbGameEntry` it
+    /// returns and keys its cached token, so two differently-named `igdb`
+    /// providers (e.g. pointing at different credentials) don't clash.
+    name: String,
+    client_id: String,
+    client_secret: String,
+    url: String,
+    token_url: String,
 }
 
 impl Igdb {
-    pub fn new() -> Self {
-        let token: GameDbToken = GameDbToken::new(IGDB_NAME.to_string());
-        Igdb { token }
+    /// Builds an `Igdb` from a configured provider entry - see
+    /// `gamedb::registry`.
+    pub fn from_config(provider: &GameDbProviderConfig) -> Self {
+        let token = GameDbToken::new(provider.name.clone());
+        Igdb {
+            token,
+            name: provider.name.clone(),
+            client_id: provider.client_id.clone(),
+            client_secret: provider.client_secret.clone(),
+            url: provider.url.clone(),
+            token_url: provider.token_url.clone(),
+        }
+    }
+
+    /// Returns a valid access token, only hitting the Twitch OAuth endpoint
+    /// (via `authenticate()`) when the cached one is missing or expired.
+    async fn ensure_token(&mut self) -> Result<String, DillingerError> {
+        let token = self.authenticate().await?;
+        self.token = token.clone();
+        token.access_token.ok_or_else(|| DillingerError {
+            description: "IGDB token response had no access_token".to_string(),
+        })
+    }
+
+    /// Posts `body` to IGDB with a valid access token, invalidating the
+    /// cached token and re-authenticating once if IGDB rejects it as expired
+    /// (a 401) despite still being inside our locally cached expiry window.
+    async fn post_authenticated(&mut self, url: &str, body: String) -> Result<serde_json::Value, WebError> {
+        let access_token = self.ensure_token().await.map_err(|e| WebError {
+            status: 0,
+            description: e.description,
+        })?;
+
+        let client_id = self.client_id.clone();
+        let headers = move |access_token: &str| {
+            let mut headers = HashMap::new();
+            headers.insert("Client-ID".to_string(), client_id.clone());
+            headers.insert("Authorization".to_string(), format!("Bearer {}", access_token));
+            headers
+        };
+
+        match post(url.to_string(), Some(headers(&access_token)), Some(body.clone())).await {
+            Err(e) if e.status == 401 => {
+                info!("IGDB rejected our cached token as expired; re-authenticating once");
+                gamedb_cache::invalidate_cached_token(&self.name);
+                let access_token = self.ensure_token().await.map_err(|e| WebError {
+                    status: 0,
+                    description: e.description,
+                })?;
+                post(url.to_string(), Some(headers(&access_token)), Some(body)).await
+            }
+            result => result,
+        }
     }
 }
 
@@ -31,25 +94,33 @@ impl GameDb for Igdb {
     // Authenticate with IGDB
     // Exchange our client_id and client_secret for an access token
     async fn authenticate(&mut self) -> Result<GameDbToken, DillingerError> {
+        // Skip the round-trip entirely while a previously issued token is
+        // still within its expiry window.
+        if let Some(token) = gamedb_cache::get_cached_token(&self.name) {
+            info!("Using cached IGDB token");
+            return Ok(token);
+        }
+
         info!("Authenticating with IGDB");
 
-        // TODO: Get this from config
-        let client_id = "lpzomulxapy5mrfftuxcnwidw5ob2q";
-        let client_secret = "me0k8eu07kdp2ayb5anxn05mvpzasb";
         let grant_type = "client_credentials";
 
         let url = format!(
-            "https://id.twitch.tv/oauth2/token?client_id={}&client_secret={}&grant_type={}",
-            client_id, client_secret, grant_type
+            "{}?client_id={}&client_secret={}&grant_type={}",
+            self.token_url, self.client_id, self.client_secret, grant_type
         );
 
         match post(url, None, None).await {
-            Ok(json) => Ok(GameDbToken {
-                db: IGDB_NAME.to_string(),
-                id_token: None, // IGDB doesn't use id_tokens
-                access_token: json["access_token"].as_str().map(|s| s.to_string()),
-                expires_in: json["expires_in"].as_u64().map(|e| e),
-            }),
+            Ok(json) => {
+                let token = GameDbToken {
+                    db: self.name.clone(),
+                    id_token: None, // IGDB doesn't use id_tokens
+                    access_token: json["access_token"].as_str().map(|s| s.to_string()),
+                    expires_in: json["expires_in"].as_u64().map(|e| e),
+                };
+                gamedb_cache::put_cached_token(&self.name, &token);
+                Ok(token)
+            }
             Err(error) => {
                 error!("Error: {}", error);
                 Err(DillingerError {
@@ -62,84 +133,72 @@ impl GameDb for Igdb {
     // Search IDGB by game title
     // We're after enough information to display a search result only
     async fn search_game(&mut self, name: &str) -> Vec<GameDbGameEntry> {
-        // First, authenticate to IGDB.
-        // TODO: add caching so we don't authenticate every time
-        let token = match self.authenticate().await {
-            Ok(token) => {
-                info!("token is {:?}", token);
-                token
-            }
-            Err(_) => {
-                // Not interested in the error here - just return an empty list
-                return vec![];
-            }
-        };
+        let master_config = config::get_master_config();
 
-        if let Some(token) = Some(token) {
-            self.token = token.clone();
-            let url = format!("{}", IGDB_URL);
+        if let Some(cached) = gamedb_cache::get_cached::<Vec<GameDbGameEntry>>(
+            SEARCH_TREE,
+            &self.name,
+            "search_game",
+            name,
+            master_config.gamedb_cache_ttl_secs,
+        ) {
+            info!("Serving search_game({}) from cache", name);
+            return cached;
+        }
 
-            let mut headers = HashMap::new();
-            headers.insert(
-                "Client-ID".to_string(),
-                "lpzomulxapy5mrfftuxcnwidw5ob2q".to_string(),
-            );
-            headers.insert(
-                "Authorization".to_string(),
-                format!("Bearer {}", token.access_token.unwrap())
-                    .as_str()
-                    .to_string(),
-            );
-
-            // Here we tell IGDB what we want, and the search term
-            let body = format!( "fields slug,name,summary,platforms.*,release_dates.date,involved_companies.company.name; search \"{}\"; limit 200;", name).to_string();
-
-            // Send the request over the intergalactic airwaves
-            match post(url, Some(headers), Some(body)).await {
-                Ok(json) => {
-                    let mut results = vec![];
-                    for game in json.as_array().unwrap() {
-                        // Extract the platforms - can be multiple
-                        // So build up a string of all matching platform names
-                        let mut platforms_names = vec![];
-                        if let Some(platforms) = game["platforms"].as_array() {
-                            for platform in platforms {
-                                platforms_names
-                                    .push(platform["name"].as_str().unwrap_or("").to_string());
-                            }
-                        }
-                        let platform_names = platforms_names.join(" | ");
+        if master_config.gamedb_offline_mode {
+            warn!("gamedb_offline_mode is set; not calling IGDB for search_game({})", name);
+            return vec![];
+        }
 
-                        // Extract the release dates - can be multiple
-                        // But we only want the first release date here
-                        let mut release_date: u64 = 0;
-                        if let Some(release_dates) = game["release_dates"].as_array() {
-                            if let Some(first_release_date) = release_dates.get(0) {
-                                let rd: u64 = first_release_date["date"].as_u64().unwrap_or(0);
-                                release_date = rd;
-                            }
+        // Here we tell IGDB what we want, and the search term
+        let body = format!( "fields slug,name,summary,platforms.*,release_dates.date,involved_companies.company.name; search \"{}\"; limit 200;", name).to_string();
+
+        // Send the request over the intergalactic airwaves
+        let url = self.url.clone();
+        match self.post_authenticated(&url, body).await {
+            Ok(json) => {
+                let mut results = vec![];
+                for game in json.as_array().unwrap() {
+                    // Extract the platforms - can be multiple
+                    // So build up a string of all matching platform names
+                    let mut platforms_names = vec![];
+                    if let Some(platforms) = game["platforms"].as_array() {
+                        for platform in platforms {
+                            platforms_names
+                                .push(platform["name"].as_str().unwrap_or("").to_string());
                         }
+                    }
+                    let platform_names = platforms_names.join(" | ");
 
-                        // Munge and stuff the data into a GameDbGameEntry
-                        results.push(GameDbGameEntry {
-                            game_db: IGDB_NAME.to_string(),
-                            slug_game: game["slug"].as_str().unwrap_or("").to_string(),
-                            slug_platform: platform_names,
-                            name: game["name"].as_str().unwrap_or("").to_string(),
-                            description: game["summary"].as_str().unwrap_or("").to_string(),
-                            release_date: release_date,
-                        });
+                    // Extract the release dates - can be multiple
+                    // But we only want the first release date here
+                    let mut release_date: u64 = 0;
+                    if let Some(release_dates) = game["release_dates"].as_array() {
+                        if let Some(first_release_date) = release_dates.get(0) {
+                            let rd: u64 = first_release_date["date"].as_u64().unwrap_or(0);
+                            release_date = rd;
+                        }
                     }
-                    return results;
-                }
-                Err(_) => {
-                    // Not interested in the error here - just return an empty list
-                    return vec![];
+
+                    // Munge and stuff the data into a GameDbGameEntry
+                    results.push(GameDbGameEntry {
+                        game_db: self.name.clone(),
+                        slug_game: game["slug"].as_str().unwrap_or("").to_string(),
+                        slug_platform: platform_names,
+                        name: game["name"].as_str().unwrap_or("").to_string(),
+                        description: game["summary"].as_str().unwrap_or("").to_string(),
+                        release_date: release_date,
+                    });
                 }
+                gamedb_cache::put_cached(SEARCH_TREE, &self.name, "search_game", name, &results);
+                results
+            }
+            Err(_) => {
+                // Not interested in the error here - just return an empty list
+                vec![]
             }
         }
-
-        vec![]
     }
 
     async fn search_platform(&mut self, name: &str) -> Vec<String> {
@@ -147,51 +206,42 @@ impl GameDb for Igdb {
     }
 
     async fn get_game_data(&mut self, game_slug: String) -> Option<Game> {
-        // First, authenticate to IGDB.
-        // TODO: add caching so we don't authenticate every time
+        let master_config = config::get_master_config();
+
+        if let Some(cached) = gamedb_cache::get_cached::<Game>(
+            GAME_DATA_TREE,
+            &self.name,
+            "get_game_data",
+            &game_slug,
+            master_config.gamedb_cache_ttl_secs,
+        ) {
+            info!("Serving get_game_data({}) from cache", game_slug);
+            return Some(cached);
+        }
+
+        if master_config.gamedb_offline_mode {
+            warn!("gamedb_offline_mode is set; not calling IGDB for get_game_data({})", game_slug);
+            return None;
+        }
+
         info!("get_game_data for igdb: game_slug: {}", game_slug);
-        let token = match self.authenticate().await {
-            Ok(token) => {
-                info!("token is {:?}", token);
-                token
-            }
-            Err(_) => {
-                // Not interested in the error here - just return an empty list
-                return None;
-            }
-        };
 
-        if let Some(token) = Some(token) {
-            self.token = token.clone();
-            let url = format!("{}", IGDB_URL);
+        // Here we tell IGDB what we want, and the search term
+        let body = format!(
+            "fields id,slug,name,summary,storyline,url,first_release_date,collections.name,themes.name, \
+            videos.video_id,websites.url,genres.name,screenshots.image_id, cover.image_id, screenshots.image_id, \
+            artworks.image_id,artworks.url,involved_companies.company.name, \
+            involved_companies.developer,involved_companies.publisher; where slug = \"{}\";",
+            game_slug
+        )
+        .to_string();
 
-            let mut headers = HashMap::new();
-            headers.insert(
-                "Client-ID".to_string(),
-                "lpzomulxapy5mrfftuxcnwidw5ob2q".to_string(),
-            );
-            headers.insert(
-                "Authorization".to_string(),
-                format!("Bearer {}", token.access_token.unwrap())
-                    .as_str()
-                    .to_string(),
-            );
-
-            // Here we tell IGDB what we want, and the search term
-            let body = format!(
-                "fields id,slug,name,summary,storyline,url,first_release_date,collections.name,themes.name, \
-                videos.video_id,websites.url,genres.name,screenshots.image_id, cover.image_id, screenshots.image_id, \
-                artworks.image_id,artworks.url,involved_companies.company.name, \
-                involved_companies.developer,involved_companies.publisher; where slug = \"{}\";",
-                game_slug
-            )
-            .to_string();
-
-            info!("body: {}", body);
-
-            // Send the request over the intergalactic airwaves
-            match post(url, Some(headers), Some(body)).await {
-                Ok(json) => {
+        info!("body: {}", body);
+
+        // Send the request over the intergalactic airwaves
+        let url = self.url.clone();
+        match self.post_authenticated(&url, body).await {
+            Ok(json) => {
                     info!("got game data: {:?}", json);
                     let mut finalGame: Game = Game::new();
                     for game in json.as_array().unwrap() {
@@ -328,25 +378,33 @@ impl GameDb for Igdb {
                         // println!("finalGame: {:?}", json.as_array());
                         // println!("---");
                     }
+                    gamedb_cache::put_cached(
+                        GAME_DATA_TREE,
+                        &self.name,
+                        "get_game_data",
+                        &game_slug,
+                        &finalGame,
+                    );
                     return Some(finalGame);
                 }
                 Err(_) => {
                     // Not interested in the error here - just return an empty list
                     info!("Error getting game data");
-                    return None;
+                    None
                 }
             }
-        } else {
-            info!("Token is None");
-            None
-        }
     }
 
     async fn get_platform_data(&mut self, id: u64, name: String) -> String {
         unimplemented!()
     }
 
+    // Queues every screenshot image_id in `screenshot_info` on the durable,
+    // retrying download queue (see `gamedb::screenshot_queue`) and waits for
+    // them to resolve. Already-downloaded images are counted without being
+    // re-queued; anything still failing when we give up waiting keeps
+    // retrying in the background for the next call to pick up.
     async fn get_screenshots(&mut self, id: u64, screenshot_info: Vec<String>) -> u32 {
-        unimplemented!()
+        screenshot_queue::fetch_all(&id.to_string(), "screenshot_big", screenshot_info).await
     }
 }
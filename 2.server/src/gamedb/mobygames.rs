@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use log::{info, warn};
+
+use super::gamedb::{GameDb, GameDbGameEntry};
+use super::gamedb_cache;
+use super::gamedbtoken::GameDbToken;
+use super::registry::GameDbProviderConfig;
+use crate::config;
+use crate::entities::dillinger_error::DillingerError;
+use crate::entities::game::Game;
+use crate::handlers::web_request::get;
+use crate::platform::Platform;
+
+const SEARCH_TREE: &str = "mobygames_search";
+const GAME_DATA_TREE: &str = "mobygames_game_data";
+
+/// MobyGames authenticates every request with a static `api_key` query
+/// param rather than an exchanged token, so there's no round-trip or cached
+/// token to maintain - `authenticate()` just hands back an empty token.
+pub struct MobyGames {
+    name: String,
+    api_key: String,
+    url: String,
+}
+
+impl MobyGames {
+    /// Builds a `MobyGames` from a configured provider entry - reuses
+    /// `client_id` to carry the api key, since MobyGames has no client
+    /// secret or OAuth exchange - see `gamedb::registry`.
+    pub fn from_config(provider: &GameDbProviderConfig) -> Self {
+        MobyGames {
+            name: provider.name.clone(),
+            api_key: provider.client_id.clone(),
+            url: provider.url.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl GameDb for MobyGames {
+    async fn authenticate(&mut self) -> Result<GameDbToken, DillingerError> {
+        Ok(GameDbToken::new(self.name.clone()))
+    }
+
+    async fn search_game(&mut self, name: &str) -> Vec<GameDbGameEntry> {
+        let master_config = config::get_master_config();
+
+        if let Some(cached) = gamedb_cache::get_cached::<Vec<GameDbGameEntry>>(
+            SEARCH_TREE,
+            &self.name,
+            "search_game",
+            name,
+            master_config.gamedb_cache_ttl_secs,
+        ) {
+            info!("Serving search_game({}) from cache", name);
+            return cached;
+        }
+
+        if master_config.gamedb_offline_mode {
+            warn!("gamedb_offline_mode is set; not calling MobyGames for search_game({})", name);
+            return vec![];
+        }
+
+        let url = format!(
+            "{}?api_key={}&format=normal&title={}",
+            self.url,
+            self.api_key,
+            urlencoding::encode(name)
+        );
+
+        let json = match get(url, None).await {
+            Ok(json) => json,
+            Err(error) => {
+                warn!("Error calling MobyGames search_game: {}", error);
+                return vec![];
+            }
+        };
+
+        let mut results = vec![];
+        if let Some(games) = json["games"].as_array() {
+            for game in games {
+                let mut platform_names = vec![];
+                if let Some(platforms) = game["platforms"].as_array() {
+                    for platform in platforms {
+                        platform_names.push(platform["platform_name"].as_str().unwrap_or("").to_string());
+                    }
+                }
+
+                results.push(GameDbGameEntry {
+                    game_db: self.name.clone(),
+                    slug_game: game["game_id"].as_u64().map(|id| id.to_string()).unwrap_or_default(),
+                    slug_platform: platform_names.join(" | "),
+                    name: game["title"].as_str().unwrap_or("").to_string(),
+                    description: game["description"].as_str().unwrap_or("").to_string(),
+                    release_date: 0,
+                });
+            }
+        }
+
+        gamedb_cache::put_cached(SEARCH_TREE, &self.name, "search_game", name, &results);
+        results
+    }
+
+    async fn search_platform(&mut self, _name: &str) -> Vec<String> {
+        unimplemented!()
+    }
+
+    async fn get_game_data(&mut self, game_slug: String) -> Option<Game> {
+        let master_config = config::get_master_config();
+
+        if let Some(cached) = gamedb_cache::get_cached::<Game>(
+            GAME_DATA_TREE,
+            &self.name,
+            "get_game_data",
+            &game_slug,
+            master_config.gamedb_cache_ttl_secs,
+        ) {
+            info!("Serving get_game_data({}) from cache", game_slug);
+            return Some(cached);
+        }
+
+        if master_config.gamedb_offline_mode {
+            warn!("gamedb_offline_mode is set; not calling MobyGames for get_game_data({})", game_slug);
+            return None;
+        }
+
+        // MobyGames has no concept of a slug - `search_game` stuffs the
+        // numeric `game_id` into `slug_game` instead, and we look it back up
+        // by `id` here.
+        let url = format!(
+            "{}?api_key={}&format=normal&id={}",
+            self.url,
+            self.api_key,
+            urlencoding::encode(&game_slug)
+        );
+
+        let json = match get(url, None).await {
+            Ok(json) => json,
+            Err(error) => {
+                warn!("Error calling MobyGames get_game_data: {}", error);
+                return None;
+            }
+        };
+
+        let game = json["games"].as_array().and_then(|games| games.get(0))?;
+
+        let game_data = Game {
+            name: game["title"].as_str().unwrap_or("").to_string(),
+            slug: game_slug.clone(),
+            summary: game["description"].as_str().unwrap_or("").to_string(),
+            for_platform: Platform::default(),
+            covers: game["sample_cover"]["image"].as_str().map(|s| vec![s.to_string()]),
+            genres: game["genres"].as_array().and_then(|arr| {
+                if arr.is_empty() {
+                    None
+                } else {
+                    Some(
+                        arr.iter()
+                            .filter_map(|item| item["genre_name"].as_str().map(|s| s.to_string()))
+                            .collect::<Vec<String>>(),
+                    )
+                }
+            }),
+            websites: game["moby_url"].as_str().map(|s| vec![s.to_string()]),
+            ..Game::new()
+        };
+
+        gamedb_cache::put_cached(GAME_DATA_TREE, &self.name, "get_game_data", &game_slug, &game_data);
+        Some(game_data)
+    }
+
+    async fn get_platform_data(&mut self, _id: u64, _name: String) -> String {
+        unimplemented!()
+    }
+
+    async fn get_screenshots(&mut self, _id: u64, _screenshot_info: Vec<String>) -> u32 {
+        // MobyGames screenshots aren't wired into the download queue yet.
+        0
+    }
+}
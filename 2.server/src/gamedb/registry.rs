@@ -0,0 +1,80 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use super::gamedb::GameDb;
+use super::igdb::Igdb;
+use super::mobygames::MobyGames;
+use super::screenscraper::ScreenScraper;
+
+/// One configured `GameDb` backend - credentials and endpoints live here
+/// instead of being hardcoded in the implementation, so a deployment can
+/// rotate a key or point at a self-hosted mirror without a rebuild, and a
+/// second backend of the same `kind` can run under a different `name`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameDbProviderConfig {
+    /// Which `GameDb` implementation to construct - `"igdb"`, `"mobygames"`
+    /// or `"screenscraper"`.
+    pub kind: String,
+    /// Identifies this provider as `GameDbGameEntry::game_db` and as the
+    /// `search_db` route/RPC parameter that selects it.
+    pub name: String,
+    #[serde(default = "default_provider_enabled")]
+    pub enabled: bool,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default = "default_igdb_url")]
+    pub url: String,
+    #[serde(default = "default_igdb_token_url")]
+    pub token_url: String,
+    /// ScreenScraper's per-user `ssid` - unused by other kinds.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// ScreenScraper's per-user `sspassword` - unused by other kinds.
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+fn default_provider_enabled() -> bool {
+    true
+}
+
+fn default_igdb_url() -> String {
+    "https://api.igdb.com/v4/games".to_string()
+}
+
+fn default_igdb_token_url() -> String {
+    "https://id.twitch.tv/oauth2/token".to_string()
+}
+
+/// Constructs a `GameDb` for `provider`, or `None` if its `kind` isn't
+/// recognized - so a typo in config is logged and skipped rather than
+/// panicking the whole registry.
+fn build_one(provider: &GameDbProviderConfig) -> Option<Box<dyn GameDb>> {
+    match provider.kind.as_str() {
+        "igdb" => Some(Box::new(Igdb::from_config(provider))),
+        "mobygames" => Some(Box::new(MobyGames::from_config(provider))),
+        "screenscraper" => Some(Box::new(ScreenScraper::from_config(provider))),
+        other => {
+            warn!("Unknown game_dbs kind {:?} for provider {:?}; skipping", other, provider.name);
+            None
+        }
+    }
+}
+
+/// Builds every enabled provider listed in `MasterConfig::game_dbs`.
+pub fn build_enabled(game_dbs: &[GameDbProviderConfig]) -> Vec<Box<dyn GameDb>> {
+    game_dbs
+        .iter()
+        .filter(|provider| provider.enabled)
+        .filter_map(build_one)
+        .collect()
+}
+
+/// Builds the single provider named `name`, or `None` if it's missing or
+/// disabled.
+pub fn build_named(game_dbs: &[GameDbProviderConfig], name: &str) -> Option<Box<dyn GameDb>> {
+    game_dbs
+        .iter()
+        .find(|provider| provider.enabled && provider.name == name)
+        .and_then(build_one)
+}
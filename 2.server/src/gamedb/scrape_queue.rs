@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+
+use super::registry;
+use crate::handlers::{cache, files};
+
+/// How many failed attempts a scrape job gets before it's given up on and
+/// left in `failed/` for good.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Base delay for the exponential backoff between retries, in seconds -
+/// doubled per attempt.
+const BACKOFF_BASE_SECS: u64 = 30;
+
+/// How many scrape jobs run concurrently.
+const WORKER_COUNT: usize = 4;
+
+/// Durable background queue for the slow `GameDb::get_game_data`
+/// call, so importing a whole library doesn't block on it title by title and
+/// a network blip doesn't lose progress - mirrors the atomic-claim,
+/// exponential-backoff design of `gamedb::screenshot_queue`, but persists
+/// jobs as plain JSON files under the cache dir instead of a sled tree, per
+/// this subsystem's own `ScrapeJob`-per-file design. Single-title lookups
+/// (e.g. a user searching and picking one result) still call `GameDb`
+/// directly; this queue is for bulk imports.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ScrapeJob {
+    gamedb: String,
+    slug: String,
+    attempts: u32,
+    next_attempt_at: u64,
+}
+
+impl ScrapeJob {
+    /// Sanitized so it's safe to use as a filename across all three queue
+    /// directories, and stable so the same `(gamedb, slug)` always maps back
+    /// to the same file - the basis of this queue's de-duplication.
+    fn file_name(&self) -> String {
+        let safe_slug: String = self
+            .slug
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{}-{}.json", self.gamedb, safe_slug)
+    }
+}
+
+fn queue_root() -> PathBuf {
+    files::get_cache_dir().join("scrape_queue")
+}
+
+fn pending_dir() -> PathBuf {
+    queue_root().join("pending")
+}
+
+fn in_progress_dir() -> PathBuf {
+    queue_root().join("in_progress")
+}
+
+fn failed_dir() -> PathBuf {
+    queue_root().join("failed")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+static WORKERS_STARTED: OnceCell<()> = OnceCell::const_new();
+
+/// Ensures the worker pool is running, so the first caller into this module
+/// starts it rather than requiring a dedicated call from `main`.
+async fn ensure_workers_started() {
+    WORKERS_STARTED
+        .get_or_init(|| async {
+            for worker_id in 0..WORKER_COUNT {
+                tokio::spawn(worker_loop(worker_id));
+            }
+        })
+        .await;
+}
+
+/// Enqueues a `get_game_data` scrape for `gamedb`/`slug`, unless one is
+/// already pending or in progress.
+pub async fn enqueue(gamedb: &str, slug: &str) {
+    ensure_workers_started().await;
+
+    let job = ScrapeJob {
+        gamedb: gamedb.to_string(),
+        slug: slug.to_string(),
+        attempts: 0,
+        next_attempt_at: now_secs(),
+    };
+    let file_name = job.file_name();
+
+    if pending_dir().join(&file_name).exists() || in_progress_dir().join(&file_name).exists() {
+        debug!("Scrape job {} already queued", file_name);
+        return;
+    }
+
+    if let Ok(json) = serde_json::to_string(&job) {
+        files::write_file(&pending_dir().join(&file_name), json, true);
+    }
+}
+
+/// Repeatedly claims and processes the first ready job found in `pending/`
+/// (directory order, not enqueue order). Tasks
+/// just sleep when there's nothing to do rather than exiting, since new jobs
+/// can be enqueued at any time.
+async fn worker_loop(worker_id: usize) {
+    loop {
+        match claim_next_ready_job() {
+            Some(job) => process_job(job).await,
+            None => tokio::time::sleep(std::time::Duration::from_secs(1)).await,
+        }
+        debug!("Scrape worker {} idle cycle complete", worker_id);
+    }
+}
+
+/// Scans `pending/` for a job whose backoff has elapsed and atomically
+/// claims it by renaming it into `in_progress/` - a rename is atomic on the
+/// same filesystem, so if two workers race for the same file only one
+/// rename succeeds and the loser just moves on.
+fn claim_next_ready_job() -> Option<ScrapeJob> {
+    let now = now_secs();
+
+    for path in files::get_files_in_dir(&pending_dir()) {
+        let Some(content) = files::read_file(&path) else { continue };
+        let Ok(job) = serde_json::from_str::<ScrapeJob>(&content) else { continue };
+        if job.next_attempt_at > now {
+            continue;
+        }
+
+        let claimed_path = in_progress_dir().join(job.file_name());
+        if let Some(parent) = claimed_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if std::fs::rename(&path, &claimed_path).is_ok() {
+            return Some(job);
+        }
+        // Lost the race to another worker - move on to the next candidate.
+    }
+    None
+}
+
+/// Runs `job` through the configured `GameDb` and writes a successful result
+/// into the same `"details"` search cache `get_game_details` reads from, so
+/// the background scrape actually warms that lookup; on failure, re-queues
+/// it with exponential backoff, or moves it to `failed/` for good once it
+/// has failed `MAX_ATTEMPTS` times.
+async fn process_job(mut job: ScrapeJob) {
+    let claimed_path = in_progress_dir().join(job.file_name());
+
+    let master_config = crate::config::get_master_config();
+    let Some(mut db) = registry::build_named(&master_config.game_dbs, &job.gamedb) else {
+        warn!("Scrape job {} references an unknown/disabled game db; dropping it", job.gamedb);
+        let _ = std::fs::remove_file(&claimed_path);
+        return;
+    };
+
+    match db.get_game_data(job.slug.clone()).await {
+        Some(game) => {
+            info!("Scraped {} ({})", job.slug, job.gamedb);
+            cache::write_search_cache("details", &job.gamedb, &job.slug, &game);
+            let _ = std::fs::remove_file(&claimed_path);
+        }
+        None => {
+            warn!("Could not scrape {} ({})", job.slug, job.gamedb);
+            fail_or_retry(&claimed_path, job);
+        }
+    }
+}
+
+fn fail_or_retry(claimed_path: &PathBuf, mut job: ScrapeJob) {
+    job.attempts += 1;
+
+    let target_dir = if job.attempts >= MAX_ATTEMPTS {
+        failed_dir()
+    } else {
+        job.next_attempt_at = now_secs() + BACKOFF_BASE_SECS * 2u64.pow(job.attempts - 1);
+        pending_dir()
+    };
+
+    let _ = std::fs::remove_file(claimed_path);
+    if let Ok(json) = serde_json::to_string(&job) {
+        files::write_file(&target_dir.join(job.file_name()), json, true);
+    }
+}
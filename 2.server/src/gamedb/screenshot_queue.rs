@@ -0,0 +1,382 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+
+use crate::media_store::{self, MediaStore};
+use crate::scrapers::scrapers::THUMBNAIL_SIZES;
+use crate::storage::{self, Store};
+
+/// How many failed attempts a screenshot download gets before the job is
+/// given up on and marked `Failed` for good.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Base delay for the exponential backoff between retries, in seconds -
+/// doubled per attempt, so a flaky CDN gets progressively more breathing
+/// room instead of being hammered.
+const BACKOFF_BASE_SECS: u64 = 30;
+
+/// How many screenshot downloads run concurrently.
+const WORKER_COUNT: usize = 4;
+
+/// How long `fetch_all` will wait for a game's jobs to finish before
+/// giving up and reporting whatever's done so far - the queue itself keeps
+/// retrying in the background regardless.
+const AWAIT_COMPLETION_TIMEOUT_SECS: u64 = 120;
+
+const QUEUE_SLED_FILENAME: &str = "screenshot_queue.sled";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum JobStatus {
+    Pending,
+    InProgress,
+    Done,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ScreenshotJob {
+    game_key: String,
+    image_id: String,
+    size: String,
+    attempts: u32,
+    next_attempt_at: u64,
+    status: JobStatus,
+}
+
+impl ScreenshotJob {
+    fn queue_key(&self) -> String {
+        format!("{}:{}:{}", self.game_key, self.size, self.image_id)
+    }
+
+    /// Where this job's `ScreenshotPointer` is recorded once the underlying
+    /// image has been fetched - the image bytes themselves live in the
+    /// `MediaStore`, content-addressed and deduped, rather than at this path.
+    fn pointer_key(&self) -> String {
+        format!("games/{}/screenshots/{}_{}.json", self.game_key, self.size, self.image_id)
+    }
+
+    /// IGDB serves the actual image bytes from its CDN, keyed by a size
+    /// template - see https://api-docs.igdb.com/#images.
+    fn cdn_url(&self) -> String {
+        format!("https://images.igdb.com/igdb/image/upload/t_{}/{}.jpg", self.size, self.image_id)
+    }
+}
+
+/// Points a fetched screenshot's well-known per-game slot (`pointer_key`) at
+/// the `MediaId` its bytes actually live under, so repeat lookups for the
+/// same `(game_key, size, image_id)` don't need to re-hash or re-fetch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ScreenshotPointer {
+    media_id: String,
+    /// `(long edge in px, MediaId)` for each of `THUMBNAIL_SIZES`, stored
+    /// alongside the original so thumbnails dedup and get served through the
+    /// same `/media/{id}` route instead of living at a filesystem path.
+    #[serde(default)]
+    thumbnail_media_ids: Vec<(u32, String)>,
+}
+
+static QUEUE_DB: OnceCell<sled::Db> = OnceCell::const_new();
+static WORKERS_STARTED: OnceCell<()> = OnceCell::const_new();
+
+/// Opens the queue's sled tree under `entries_dir`, so queued jobs survive a
+/// restart instead of being lost along with whatever the process was doing
+/// when it stopped. Jobs left `InProgress` by a previous process are put back
+/// to `Pending` on open, so they get claimed again.
+async fn db() -> &'static sled::Db {
+    QUEUE_DB
+        .get_or_init(|| async {
+            let config = crate::config::get_master_config();
+            let path: PathBuf = config.entries_dir.join(QUEUE_SLED_FILENAME);
+            let db = sled::open(&path)
+                .unwrap_or_else(|e| panic!("Could not open screenshot queue at {:?}: {:?}", path, e));
+            for entry in db.iter() {
+                let Ok((key, raw)) = entry else { continue };
+                let Ok(mut job) = bincode::deserialize::<ScreenshotJob>(&raw) else { continue };
+                if job.status == JobStatus::InProgress {
+                    job.status = JobStatus::Pending;
+                    if let Ok(encoded) = bincode::serialize(&job) {
+                        let _ = db.insert(key, encoded);
+                    }
+                }
+            }
+            db
+        })
+        .await
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+/// Ensures the worker pool is running, so the first caller into this module
+/// starts it rather than requiring a dedicated call from `main`.
+async fn ensure_workers_started() {
+    WORKERS_STARTED
+        .get_or_init(|| async {
+            for worker_id in 0..WORKER_COUNT {
+                tokio::spawn(worker_loop(worker_id));
+            }
+        })
+        .await;
+}
+
+/// Enqueues a screenshot download for `game_key`/`size`/`image_id`, unless
+/// it's already queued - so scraping the same game twice concurrently
+/// doesn't download the same image twice. The check for an already-fetched
+/// image is done by `fetch_all` before it calls this.
+async fn enqueue(game_key: &str, image_id: &str, size: &str) {
+    let job = ScreenshotJob {
+        game_key: game_key.to_string(),
+        image_id: image_id.to_string(),
+        size: size.to_string(),
+        attempts: 0,
+        next_attempt_at: now_secs(),
+        status: JobStatus::Pending,
+    };
+
+    let tree = db().await;
+    let key = job.queue_key();
+    if tree.contains_key(key.as_bytes()).unwrap_or(false) {
+        debug!("Screenshot job {} already queued", key);
+        return;
+    }
+    if let Ok(encoded) = bincode::serialize(&job) {
+        let _ = tree.insert(key.as_bytes(), encoded);
+    }
+}
+
+/// Enqueues `image_ids` (all at the given `size`) for `game_key` and waits up
+/// to `AWAIT_COMPLETION_TIMEOUT_SECS` for them to reach a terminal state,
+/// returning how many were fetched successfully by then - the real count
+/// `GameDb::get_screenshots` reports.
+/// The queue remains durable throughout, so if the process dies mid-wait the
+/// next call picks the same jobs back up instead of re-downloading from
+/// scratch.
+pub async fn fetch_all(game_key: &str, size: &str, image_ids: Vec<String>) -> u32 {
+    if image_ids.is_empty() {
+        return 0;
+    }
+
+    ensure_workers_started().await;
+
+    let store = storage::open(&crate::config::get_master_config());
+    let mut pending = Vec::new();
+    let mut done_count = 0;
+
+    for image_id in &image_ids {
+        let pointer_key = format!("games/{}/screenshots/{}_{}.json", game_key, size, image_id);
+        if store.len(&pointer_key).await.is_some() {
+            done_count += 1;
+            continue;
+        }
+        enqueue(game_key, image_id, size).await;
+        pending.push(image_id.clone());
+    }
+
+    let deadline = now_secs() + AWAIT_COMPLETION_TIMEOUT_SECS;
+    while now_secs() < deadline {
+        pending.retain(|image_id| {
+            let key = format!("{}:{}:{}", game_key, size, image_id);
+            match job_status(&key) {
+                Some(JobStatus::Done) => {
+                    done_count += 1;
+                    false
+                }
+                Some(JobStatus::Failed) => false,
+                _ => true,
+            }
+        });
+
+        if pending.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+
+    if !pending.is_empty() {
+        warn!(
+            "Gave up waiting on {} screenshot(s) for {} after {}s; they'll keep retrying in the background",
+            pending.len(),
+            game_key,
+            AWAIT_COMPLETION_TIMEOUT_SECS
+        );
+    }
+
+    done_count
+}
+
+fn job_status(queue_key: &str) -> Option<JobStatus> {
+    let tree = QUEUE_DB.get()?;
+    let raw = tree.get(queue_key.as_bytes()).ok().flatten()?;
+    let job: ScreenshotJob = bincode::deserialize(&raw).ok()?;
+    Some(job.status)
+}
+
+/// Repeatedly claims and processes the first ready job in the queue's key
+/// order (not enqueue order). Tasks
+/// just sleep when there's nothing to do rather than exiting, since new jobs
+/// can be enqueued at any time by any in-flight scrape.
+async fn worker_loop(worker_id: usize) {
+    loop {
+        match claim_next_ready_job().await {
+            Some(job) => process_job(job).await,
+            None => tokio::time::sleep(std::time::Duration::from_secs(1)).await,
+        }
+        debug!("Screenshot worker {} idle cycle complete", worker_id);
+    }
+}
+
+/// Scans the queue for a `Pending` job whose backoff has elapsed (retries are
+/// put back to `Pending`; `Failed` is terminal) and atomically marks it
+/// `InProgress`, so two workers can't pick up the same job.
+async fn claim_next_ready_job() -> Option<ScreenshotJob> {
+    let tree = db().await;
+    let now = now_secs();
+
+    for entry in tree.iter() {
+        let Ok((key, raw)) = entry else { continue };
+        let Ok(mut job) = bincode::deserialize::<ScreenshotJob>(&raw) else {
+            continue;
+        };
+        let ready = job.status == JobStatus::Pending && job.next_attempt_at <= now;
+        if !ready {
+            continue;
+        }
+
+        job.status = JobStatus::InProgress;
+        let Ok(encoded) = bincode::serialize(&job) else {
+            continue;
+        };
+        if matches!(tree.compare_and_swap(key, Some(raw), Some(encoded)), Ok(Ok(()))) {
+            return Some(job);
+        }
+        // Lost the race to another worker - move on to the next candidate.
+    }
+    None
+}
+
+/// Downloads `job`'s image, stores it in the `MediaStore` (deduped by
+/// content hash) and records a `ScreenshotPointer` at its well-known
+/// per-game slot; on failure, re-enqueues it with exponential backoff, or
+/// marks it `Failed` for good once it has failed `MAX_ATTEMPTS` times.
+async fn process_job(mut job: ScreenshotJob) {
+    let client = reqwest::Client::new();
+    let url = job.cdn_url();
+
+    let result = async {
+        let response = client.get(&url).send().await?.error_for_status()?;
+        response.bytes().await
+    }
+    .await;
+
+    let tree = db().await;
+    let key = job.queue_key();
+
+    match result {
+        Ok(bytes) => {
+            let config = crate::config::get_master_config();
+            let media = media_store::open(&config);
+            match media.put(&bytes, "image/jpeg").await {
+                Ok(media_id) => {
+                    let thumbnail_media_ids = generate_thumbnails(&media, &bytes).await;
+                    let pointer = ScreenshotPointer {
+                        media_id: media_id.as_str().to_string(),
+                        thumbnail_media_ids,
+                    };
+                    let store = storage::open(&config);
+                    let write_result = match serde_json::to_vec(&pointer) {
+                        Ok(json) => store.write(&job.pointer_key(), &json).await,
+                        Err(e) => Err(format!("Could not serialize screenshot pointer: {}", e)),
+                    };
+                    match write_result {
+                        Ok(()) => {
+                            info!("Fetched screenshot {} for {}", job.image_id, job.game_key);
+                            job.status = JobStatus::Done;
+                            if let Ok(encoded) = bincode::serialize(&job) {
+                                let _ = tree.insert(key.as_bytes(), encoded);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Could not record screenshot pointer for {} ({}): {}", job.image_id, job.game_key, e);
+                            fail_or_retry(&tree, job);
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("Could not store screenshot {} for {}: {}", job.image_id, job.game_key, e);
+                    fail_or_retry(&tree, job);
+                }
+            }
+        }
+        Err(e) => {
+            warn!("Could not fetch screenshot {} for {}: {:?}", job.image_id, job.game_key, e);
+            fail_or_retry(&tree, job);
+        }
+    }
+}
+
+/// Decodes `original` and puts a resized variant at each of `THUMBNAIL_SIZES`
+/// (long edge, aspect preserved) into the `MediaStore`, so thumbnails get the
+/// same dedup and `/media/{id}` serving as the original instead of living at
+/// a filesystem path - the content-addressed counterpart to
+/// `scrapers::igdb::generate_thumbnails`, which does the equivalent for the
+/// legacy file-path-based scraper. Runs on a blocking thread since
+/// image decode/resize is CPU-bound.
+async fn generate_thumbnails(media: &Arc<dyn MediaStore>, original: &[u8]) -> Vec<(u32, String)> {
+    let original = original.to_vec();
+    let resized = tokio::task::spawn_blocking(move || -> Result<Vec<(u32, Vec<u8>)>, image::ImageError> {
+        let image = image::load_from_memory(&original)?;
+        let mut resized = Vec::new();
+        for size in THUMBNAIL_SIZES {
+            let mut buf = Vec::new();
+            image
+                .thumbnail(size, size)
+                .write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)?;
+            resized.push((size, buf));
+        }
+        Ok(resized)
+    })
+    .await;
+
+    let resized = match resized {
+        Ok(Ok(resized)) => resized,
+        Ok(Err(e)) => {
+            warn!("Could not generate thumbnails: {}", e);
+            return Vec::new();
+        }
+        Err(e) => {
+            warn!("Thumbnail generation panicked: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut ids = Vec::new();
+    for (size, bytes) in resized {
+        match media.put(&bytes, "image/png").await {
+            Ok(id) => ids.push((size, id.as_str().to_string())),
+            Err(e) => warn!("Could not store {}px thumbnail: {}", size, e),
+        }
+    }
+    ids
+}
+
+fn fail_or_retry(tree: &sled::Db, mut job: ScreenshotJob) {
+    job.attempts += 1;
+    if job.attempts >= MAX_ATTEMPTS {
+        job.status = JobStatus::Failed;
+    } else {
+        job.status = JobStatus::Pending;
+        job.next_attempt_at = now_secs() + BACKOFF_BASE_SECS * 2u64.pow(job.attempts - 1);
+    }
+    let key = job.queue_key();
+    if let Ok(encoded) = bincode::serialize(&job) {
+        let _ = tree.insert(key.as_bytes(), encoded);
+    }
+}
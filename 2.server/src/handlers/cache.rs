@@ -1,21 +1,91 @@
+use std::path::PathBuf;
 use std::sync::Arc;
-use log::info;
+
+use chrono::Utc;
+use log::{debug, info, warn};
+use serde::{de::DeserializeOwned, Serialize};
 
 use crate::config;
 use crate::{config::MasterConfig, entities::game::Game};
 use crate::handlers::files;
+use crate::storage;
+
+/// How long a cached `search_title`/`get_game_details` response stays valid
+/// before a miss (or an explicit `refresh`) forces a live re-fetch from the
+/// remote game DB.
+pub const DEFAULT_SEARCH_CACHE_TTL_SECS: i64 = 24 * 60 * 60;
 
-pub fn write_cache_last_search(game: Game) {
-    // Find the directory to the last search cache
+pub async fn write_cache_last_search(game: Game) {
+    // Find the key for the last search cache, relative to the store root.
     let config = config::get_master_config();
-    info!("The config is: {:?}", config);
-    info!("The root dir is: {:?}", config.root_dir);
-    let cache_dir = &config.root_dir.join("system/search_cache");
-    let cache_file = cache_dir.join("last_search.toml");
+    let key = "system/search_cache/last_search.toml";
 
-    info!("Writing last search cache to: {:?}", cache_file);
+    info!("Writing last search cache to: {:?}", key);
 
-    // Write the file
+    // Write it through the store so this lands wherever downloads and
+    // screenshots do.
     let toml = toml::to_string(&game).unwrap();
-    files::write_file(&cache_file, toml, true);
+    if let Err(e) = storage::open(&config).write(key, toml.as_bytes()).await {
+        warn!("Could not write last search cache: {}", e);
+    }
+}
+
+fn search_cache_dir(config: &MasterConfig) -> PathBuf {
+    config.root_dir.join("system/search_cache")
+}
+
+fn search_cache_key_path(config: &MasterConfig, kind: &str, game_db: &str, key: &str) -> PathBuf {
+    let safe_key: String = key
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    search_cache_dir(config).join(format!("{}-{}-{}.json", kind, game_db, safe_key))
+}
+
+/// Reads a previously cached `search_title`/`get_game_details` response for
+/// `(kind, game_db, key)`, provided one exists and is younger than
+/// `ttl_secs`. Repeated searches and re-scrapes of the same title can then
+/// serve from disk instead of burning remote API quota.
+pub fn read_search_cache<T: DeserializeOwned>(
+    kind: &str,
+    game_db: &str,
+    key: &str,
+    ttl_secs: i64,
+) -> Option<T> {
+    #[derive(serde::Deserialize)]
+    struct CacheEnvelope<T> {
+        cached_at: chrono::DateTime<Utc>,
+        value: T,
+    }
+
+    let config = config::get_master_config();
+    let path = search_cache_key_path(&config, kind, game_db, key);
+    let content = files::read_file(&path)?;
+    let envelope: CacheEnvelope<T> = serde_json::from_str(&content).ok()?;
+
+    let age_secs = Utc::now().signed_duration_since(envelope.cached_at).num_seconds();
+    if age_secs > ttl_secs {
+        debug!("Search cache entry {:?} expired ({}s old)", path, age_secs);
+        return None;
+    }
+
+    Some(envelope.value)
+}
+
+/// Writes `value` to the on-disk search cache for `(kind, game_db, key)`.
+pub fn write_search_cache<T: Serialize>(kind: &str, game_db: &str, key: &str, value: &T) {
+    #[derive(serde::Serialize)]
+    struct CacheEnvelope<'a, T> {
+        cached_at: chrono::DateTime<Utc>,
+        value: &'a T,
+    }
+
+    let config = config::get_master_config();
+    let path = search_cache_key_path(&config, kind, game_db, key);
+    let envelope = CacheEnvelope {
+        cached_at: Utc::now(),
+        value,
+    };
+    let json = serde_json::to_string_pretty(&envelope).unwrap();
+    files::write_file(&path, json, true);
 }
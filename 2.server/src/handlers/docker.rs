@@ -4,15 +4,12 @@ use dockworker::Docker;
 use log::{info, error};
 use std::fmt;
 
+use crate::docker::docker_interactor::DockerContainer;
+use crate::helpers::docker_run_params::DockerRunParams;
+
 pub struct DockerStatus {
     pub daemon_up: bool,
 }
-#[derive(Debug)]
-pub struct DockerRunParams {
-    pub container_name: String,
-    pub image_name: String,
-    pub container_id: Option<String>,
-}
 
 #[derive(Debug)]
 pub enum DockerError {
@@ -41,22 +38,83 @@ pub async fn get_docker_daemon_status() -> Result<DockerStatus, Infallible> {
     }
 }
 
-pub async fn docker_run_container(run_params: DockerRunParams) -> Result<(DockerRunParams), DockerError> {
+/// Creates and starts a container from the full `DockerRunParams` builder,
+/// mapping its fields onto `dockworker::ContainerCreateOptions` - volumes as
+/// binds, ports as port bindings, `env_vars` into the container env, and
+/// `cmd`/`entrypoint`/`working_dir`/`user`/`hostname`/`domainname`/`network`/
+/// `network_alias`/`network_mode`/`labels` onto their matching options - so a
+/// launched game actually gets its ROM volume mounted and its display/network
+/// configured, rather than an empty `hello-world`-style container. `memory`
+/// and `nano_cpus` are not applied here, and `remove` is only logged.
+pub async fn docker_run_container(run_params: DockerRunParams) -> Result<DockerContainer, DockerError> {
     let docker = Docker::connect_with_defaults().unwrap();
-    let mut create = ContainerCreateOptions::new(&run_params.container_name);
-    create.tty(true);
+
+    let container_name = run_params
+        .name
+        .clone()
+        .unwrap_or_else(|| run_params.image_name.clone());
+
+    let mut create = ContainerCreateOptions::new(&container_name);
+
+    create.tty(run_params.tty.unwrap_or(false));
+    create.open_stdin(run_params.interactive.unwrap_or(false));
+
+    if let Some(volumes) = &run_params.volumes {
+        create.volumes(volumes.clone());
+    }
+    if let Some(ports) = &run_params.ports {
+        create.ports(ports.clone());
+    }
+    if let Some(env_vars) = &run_params.env_vars {
+        create.env(env_vars.clone());
+    }
+    if let Some(cmd) = &run_params.cmd {
+        create.cmd(cmd.clone());
+    }
+    if let Some(entrypoint) = &run_params.entrypoint {
+        create.entrypoint(vec![entrypoint.clone()]);
+    }
+    if let Some(working_dir) = &run_params.working_dir {
+        create.working_dir(working_dir.clone());
+    }
+    if let Some(user) = &run_params.user {
+        create.user(user.clone());
+    }
+    if let Some(hostname) = &run_params.hostname {
+        create.hostname(hostname.clone());
+    }
+    if let Some(domainname) = &run_params.domainname {
+        create.domainname(domainname.clone());
+    }
+    if let Some(network) = &run_params.network {
+        create.network(network.clone());
+    }
+    if let Some(network_alias) = &run_params.network_alias {
+        create.network_alias(network_alias.clone());
+    }
+    if let Some(network_mode) = &run_params.network_mode {
+        create.network_mode(network_mode.clone());
+    }
+    if let Some(labels) = &run_params.labels {
+        create.labels(labels.clone());
+    }
+
     let container = docker
         .create_container(Some(&run_params.image_name), &create)
         .await
-        .unwrap();
+        .map_err(|e| DockerError::StartError(format!("Failed to create container: {:?}", e)))?;
 
     match docker.start_container(&container.id).await {
         Ok(_) => {
-            info!("Started Container {:?} with an ID of: {:?}", &run_params.container_name, &container.id);
-            let mut run_params_out = run_params;
-            run_params_out.container_id = Some(container.id);
-            Ok(run_params_out)
-        },
+            info!("Started Container {:?} with an ID of: {:?}", container_name, &container.id);
+            if run_params.remove.unwrap_or(false) {
+                info!("Container {:?} was requested to auto-remove on exit", &container.id);
+            }
+            Ok(DockerContainer {
+                id: container.id,
+                image: run_params.image_name,
+            })
+        }
         Err(e) => {
             error!("Failed to start container: {:?}", e);
             Err(DockerError::StartError(format!("Failed to start container: {:?}", e)))
@@ -73,12 +131,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_docker_run_container() {
-        let result = docker_run_container(DockerRunParams {
-            container_name: "hello-world".to_string(),
-            image_name: "test_hello_world".to_string(),
-            container_id: None,
-        })
-        .await;
+        let run_params = DockerRunParams::new("test_hello_world".to_string())
+            .cmd(vec!["echo".to_string(), "hello-world".to_string()])
+            .tty(true)
+            .build();
+        let result = docker_run_container(run_params).await;
         assert!(result.is_ok());
     }
 }
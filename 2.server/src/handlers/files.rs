@@ -23,6 +23,21 @@ pub fn get_dirs_in_dir(dir_path: &PathBuf) -> Vec<PathBuf> {
     dirs
 }
 
+pub fn get_files_in_dir(dir_path: &PathBuf) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir_path) {
+        for entry in entries {
+            if let Ok(entry) = entry {
+                let path = entry.path();
+                if path.is_file() {
+                    files.push(path);
+                }
+            }
+        }
+    }
+    files
+}
+
 pub fn read_file(path: &PathBuf) -> Option<String> {
     let file_str = match fs::read_to_string(path) {
         Ok(content) => Some(content),
@@ -1,8 +1,9 @@
 use axum::{extract::Json, http::StatusCode, response::IntoResponse};
-use std::{collections::HashMap, os::macos::raw::stat};
+use std::collections::HashMap;
 use log::info;
 
 use crate::handlers::docker;
+use crate::helpers::docker_run_params::DockerRunParams;
 
 pub struct Game {
     pub slug: String,
@@ -11,11 +12,9 @@ pub struct Game {
 pub async fn game_launch(Json(body): Json<HashMap<String, String>>) -> impl IntoResponse {
     info!("Game launch function executed - got the following body: {:?}", body);
 
-    let run_params = docker::DockerRunParams {
-        container_name: "hello-world".to_string(),
-        image_name: "test_hello_world".to_string(),
-        container_id: None,
-    };
+    let run_params = DockerRunParams::new("test_hello_world".to_string())
+        .name("hello-world".to_string())
+        .build();
 
     match docker::docker_run_container(run_params).await {
         Ok(run_params_out) => {
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info, Instrument};
+
+use crate::docker::docker_interactor;
+use crate::entities::game::Game;
+use crate::gamedb::gamedb_search;
+use crate::helpers::docker_run_params::DockerRunParams;
+use crate::network::network_manager;
+
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const INTERNAL_ERROR: i32 = -32603;
+
+#[derive(Debug, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    pub id: Option<Value>,
+    pub method: String,
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonRpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    fn ok(id: Option<Value>, result: Value) -> Self {
+        JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn err(id: Option<Value>, code: i32, message: String) -> Self {
+        JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError { code, message }),
+        }
+    }
+}
+
+/// Parses a raw text frame and, if it's a request (has an `id`), returns the
+/// response that should be written back. Notifications (no `id`) are
+/// dispatched but produce no response, per the JSON-RPC 2.0 spec.
+pub async fn handle_message(raw: &str) -> Option<JsonRpcResponse> {
+    let request: JsonRpcRequest = match serde_json::from_str(raw) {
+        Ok(request) => request,
+        Err(e) => {
+            debug!("Failed to parse JSON-RPC request: {:?}", e);
+            return Some(JsonRpcResponse::err(None, PARSE_ERROR, "Parse error".to_string()));
+        }
+    };
+
+    if request.jsonrpc != "2.0" {
+        return Some(JsonRpcResponse::err(
+            request.id,
+            INVALID_REQUEST,
+            "Invalid request: jsonrpc must be \"2.0\"".to_string(),
+        ));
+    }
+
+    let id = request.id.clone();
+    let request_id = id
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let span = tracing::info_span!("jsonrpc", request_id = %request_id, method = %request.method);
+    let result = dispatch(&request).instrument(span).await;
+
+    // A notification carries no id, so per spec we never reply - even on error.
+    if id.is_none() {
+        return None;
+    }
+
+    Some(match result {
+        Ok(value) => JsonRpcResponse::ok(id, value),
+        Err((code, message)) => JsonRpcResponse::err(id, code, message),
+    })
+}
+
+async fn dispatch(request: &JsonRpcRequest) -> Result<Value, (i32, String)> {
+    info!("Dispatching JSON-RPC method: {}", request.method);
+
+    match request.method.as_str() {
+        "docker.status" => {
+            let status = docker_interactor::get_docker_daemon_status().await;
+            Ok(serde_json::to_value(status).unwrap())
+        }
+        "game.search" => {
+            let params = request
+                .params
+                .clone()
+                .ok_or((INVALID_PARAMS, "Expected params: { db, term }".to_string()))?;
+            let db = params["db"]
+                .as_str()
+                .ok_or((INVALID_PARAMS, "Missing string param: db".to_string()))?
+                .to_string();
+            let term = params["term"]
+                .as_str()
+                .ok_or((INVALID_PARAMS, "Missing string param: term".to_string()))?
+                .to_string();
+            let refresh = params["refresh"].as_bool().unwrap_or(false);
+
+            match gamedb_search::search_title(db, term, refresh).await {
+                Ok(results) => Ok(serde_json::to_value(results).unwrap()),
+                Err(e) => Err((INVALID_PARAMS, e.description)),
+            }
+        }
+        "game.launch" => {
+            let params = request
+                .params
+                .clone()
+                .ok_or((INVALID_PARAMS, "Expected params: Game".to_string()))?;
+            let game: Game = serde_json::from_value(params)
+                .map_err(|e| (INVALID_PARAMS, format!("Invalid Game params: {:?}", e)))?;
+
+            let run_params = DockerRunParams::new(game.slug.clone())
+                .name(game.slug.clone())
+                .remove(true)
+                .build();
+            match docker_interactor::docker_run(run_params).await {
+                Ok(_) => Ok(serde_json::json!({ "slug": game.slug, "accepted": true })),
+                Err(e) => Err((INTERNAL_ERROR, format!("Failed to launch {}: {:?}", game.slug, e))),
+            }
+        }
+        "transfer.start" => {
+            let params = request.params.clone().ok_or((
+                INVALID_PARAMS,
+                "Expected params: { url, destination }".to_string(),
+            ))?;
+            let url = params["url"]
+                .as_str()
+                .ok_or((INVALID_PARAMS, "Missing string param: url".to_string()))?
+                .to_string();
+            let destination = params["destination"]
+                .as_str()
+                .ok_or((INVALID_PARAMS, "Missing string param: destination".to_string()))?
+                .to_string();
+
+            let transfer_id = network_manager::add_file_transfer(url.clone(), destination.clone().into())
+                .await
+                .ok_or((INTERNAL_ERROR, "Server is shutting down; refusing new transfer".to_string()))?;
+            if let Some(id) = request.id.as_ref() {
+                network_manager::set_correlation_id(transfer_id, id.to_string()).await;
+            }
+            tokio::spawn(network_manager::start_file_transfer(transfer_id, url));
+
+            Ok(serde_json::json!({ "transfer_id": transfer_id.to_string() }))
+        }
+        "transfer.cancel" => {
+            let params = request
+                .params
+                .clone()
+                .ok_or((INVALID_PARAMS, "Expected params: { transfer_id }".to_string()))?;
+            let transfer_id = params["transfer_id"]
+                .as_str()
+                .ok_or((INVALID_PARAMS, "Missing string param: transfer_id".to_string()))?;
+            let transfer_id = uuid::Uuid::parse_str(transfer_id)
+                .map_err(|e| (INVALID_PARAMS, format!("Invalid transfer_id: {:?}", e)))?;
+
+            network_manager::remove_file_transfer(transfer_id).await;
+            Ok(serde_json::json!({ "cancelled": true }))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", request.method))),
+    }
+}
@@ -2,16 +2,11 @@ use warp::Filter;
 use warp::ws::{Message, WebSocket};
 use tokio::sync::{mpsc, RwLock};
 use futures::{FutureExt, StreamExt};
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug)]
-struct Payload {
-    id: String,
-    message: String,
-}
+use super::jsonrpc;
 
 pub type Clients = Arc<RwLock<HashMap<String, mpsc::UnboundedSender<Result<Message, warp::Error>>>>>;
 
@@ -39,8 +34,13 @@ pub async fn client_connection(ws: WebSocket) {
         match result {
             Ok(msg) => {
                 if msg.is_text() {
-                    let payload: Payload = serde_json::from_str(msg.to_str().unwrap()).unwrap();
-                    println!("Received message from {}: {}", client_id, payload.message);
+                    if let Some(response) = jsonrpc::handle_message(msg.to_str().unwrap()).await {
+                        let json_payload = serde_json::to_string(&response).unwrap();
+                        let clients = clients.read().await;
+                        if let Some(tx) = clients.get(&client_id) {
+                            let _ = tx.send(Ok(Message::text(json_payload)));
+                        }
+                    }
                 }
             }
             Err(e) => {
@@ -38,6 +38,35 @@ impl From<reqwest::Error> for WebError {
     }
 }
 
+/// Issues a GET, e.g. for query-param-authenticated APIs like ScreenScraper
+/// or MobyGames that don't take a request body.
+pub async fn get(url: String, headers: Option<HashMap<String, String>>) -> Result<serde_json::Value, WebError> {
+    let client = reqwest::Client::new();
+
+    let mut reqHeaders = HeaderMap::new();
+    reqHeaders.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    reqHeaders.insert(USER_AGENT, HeaderValue::from_static("reqwest"));
+
+    if let Some(headers) = headers {
+        for (key, value) in headers.iter() {
+            reqHeaders.insert(
+                HeaderName::from_bytes(key.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+    }
+
+    let res = client
+        .get(&url)
+        .headers(reqHeaders)
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+
+    Ok(res)
+}
+
 pub async fn post(
     url: String,
     headers: Option<HashMap<String, String>>,
@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+
 use serde::Serialize;
 
+use crate::docker::docker_interactor::DockerEnvironmentMappingExt;
+
 #[derive(Debug, Serialize)]
 pub struct DockerRunParams {
     pub image_name: String,
@@ -20,6 +24,10 @@ pub struct DockerRunParams {
     pub working_dir: Option<String>,
     pub entrypoint: Option<String>,
     pub labels: Option<Vec<String>>,
+    /// Hard memory cap in bytes, wired into `HostConfig::memory`.
+    pub memory: Option<u64>,
+    /// CPU quota in billionths of a CPU, wired into `HostConfig::nano_cpus`.
+    pub nano_cpus: Option<u64>,
 }
 
 impl DockerRunParams {
@@ -43,6 +51,8 @@ impl DockerRunParams {
             working_dir: None,
             entrypoint: None,
             labels: None,
+            memory: None,
+            nano_cpus: None,
         }
     }
 
@@ -51,6 +61,38 @@ impl DockerRunParams {
         self
     }
 
+    pub fn ports(mut self, ports: Vec<String>) -> Self {
+        self.ports = Some(ports);
+        self
+    }
+
+    /// Accepts a plain `KEY -> value` map and formats it into the
+    /// `KEY=value` entries docker expects, via `DockerEnvironmentMappingExt`.
+    pub fn env(mut self, env: HashMap<String, String>) -> Self {
+        self.env_vars = Some(env.format_entries());
+        self
+    }
+
+    pub fn cmd(mut self, cmd: Vec<String>) -> Self {
+        self.cmd = Some(cmd);
+        self
+    }
+
+    pub fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn memory(mut self, memory: u64) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    pub fn nano_cpus(mut self, nano_cpus: u64) -> Self {
+        self.nano_cpus = Some(nano_cpus);
+        self
+    }
+
     pub fn interactive(mut self, interactive: bool) -> Self {
         self.interactive = Some(interactive);
         self
@@ -88,6 +130,8 @@ impl DockerRunParams {
             working_dir: self.working_dir,
             entrypoint: self.entrypoint,
             labels: self.labels,
+            memory: self.memory,
+            nano_cpus: self.nano_cpus,
         }
     }
 }
\ No newline at end of file
@@ -1,14 +1,21 @@
+use std::os::unix::io::AsRawFd;
 use std::sync::Arc;
+
+use log::{debug, info, warn};
 use serde::Serialize;
+use tokio::io::unix::AsyncFd;
 use tokio::sync::Mutex;
-use udev::MonitorBuilder;
+use udev::{Event, EventType, MonitorBuilder};
+
+use crate::handlers::socket_client;
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Debug)]
 struct KeyboardInfo {
     manufacturer: String,
     product: String,
 }
 
+#[derive(Serialize, Clone, Debug)]
 struct GamePadInfo {
     manufacturer: String,
     product: String,
@@ -16,12 +23,121 @@ struct GamePadInfo {
 
 lazy_static! {
     static ref KEYBOARDS: Arc<Mutex<Vec<KeyboardInfo>>> = Arc::new(Mutex::new(Vec::new()));
+    static ref GAMEPADS: Arc<Mutex<Vec<GamePadInfo>>> = Arc::new(Mutex::new(Vec::new()));
 }
 
+// Sent to websocket clients whenever a keyboard or gamepad is plugged in or
+// unplugged, so the frontend can refresh its input config at launch time.
+#[derive(Serialize)]
+struct DeviceChangeMessage {
+    component: String,
+    keyboards: Vec<KeyboardInfo>,
+    gamepads: Vec<GamePadInfo>,
+}
+
+/// Watches the `input` udev subsystem for `add`/`remove` events, classifies
+/// them into keyboards vs. gamepads, keeps `KEYBOARDS`/`GAMEPADS` in sync and
+/// pushes the updated lists to connected websocket clients.
 pub async fn monitor_devices() {
-    let monitor = MonitorBuilder::new().unwrap()
-        .match_subsystem("input").unwrap()
-        .listen().unwrap();
+    let socket = match MonitorBuilder::new()
+        .and_then(|builder| builder.match_subsystem("input"))
+        .and_then(|builder| builder.listen())
+    {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!("Could not start udev input monitor: {:?}", e);
+            return;
+        }
+    };
+
+    let mut async_fd = match AsyncFd::new(socket) {
+        Ok(async_fd) => async_fd,
+        Err(e) => {
+            warn!("Could not watch udev monitor socket: {:?}", e);
+            return;
+        }
+    };
+
+    info!("Watching udev input subsystem for device hotplug events");
+
+    loop {
+        let mut guard = match async_fd.readable_mut().await {
+            Ok(guard) => guard,
+            Err(e) => {
+                warn!("udev monitor socket error: {:?}", e);
+                return;
+            }
+        };
+
+        let events: Vec<Event> = guard.get_inner_mut().iter().collect();
+        guard.clear_ready();
+
+        if events.is_empty() {
+            continue;
+        }
+
+        for event in events {
+            handle_event(event).await;
+        }
 
-    
-}
\ No newline at end of file
+        send_device_change_message().await;
+    }
+}
+
+async fn handle_event(event: Event) {
+    let device = event.device();
+    let is_keyboard = device.property_value("ID_INPUT_KEYBOARD").is_some();
+    let is_gamepad = device.property_value("ID_INPUT_JOYSTICK").is_some();
+
+    if !is_keyboard && !is_gamepad {
+        return;
+    }
+
+    let manufacturer = device
+        .property_value("ID_VENDOR_FROM_DATABASE")
+        .or_else(|| device.property_value("ID_VENDOR"))
+        .and_then(|v| v.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let product = device
+        .property_value("ID_MODEL_FROM_DATABASE")
+        .or_else(|| device.property_value("ID_MODEL"))
+        .and_then(|v| v.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    match event.event_type() {
+        EventType::Add => {
+            debug!("Device added: {} {}", manufacturer, product);
+            if is_keyboard {
+                let mut keyboards = KEYBOARDS.lock().await;
+                keyboards.push(KeyboardInfo { manufacturer, product });
+            } else {
+                let mut gamepads = GAMEPADS.lock().await;
+                gamepads.push(GamePadInfo { manufacturer, product });
+            }
+        }
+        EventType::Remove => {
+            debug!("Device removed: {} {}", manufacturer, product);
+            if is_keyboard {
+                let mut keyboards = KEYBOARDS.lock().await;
+                keyboards.retain(|k| !(k.manufacturer == manufacturer && k.product == product));
+            } else {
+                let mut gamepads = GAMEPADS.lock().await;
+                gamepads.retain(|g| !(g.manufacturer == manufacturer && g.product == product));
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn send_device_change_message() {
+    let message = DeviceChangeMessage {
+        component: "input_devices".to_string(),
+        keyboards: KEYBOARDS.lock().await.clone(),
+        gamepads: GAMEPADS.lock().await.clone(),
+    };
+
+    let json_payload = serde_json::to_string(&message).unwrap();
+    socket_client::send_message(json_payload).await;
+}
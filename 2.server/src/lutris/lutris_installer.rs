@@ -1,8 +1,16 @@
-use serde::{Deserialize, Serialize};
-use serde_yaml::Value;
-use std::process::Command;
+use flate2::read::GzDecoder;
+use log::{debug, info};
+use serde::Deserialize;
 use std::fs;
 use std::path::Path;
+use tar::Archive;
+use tokio::process::Command;
+
+use crate::entities::dillinger_error::DillingerError;
+use crate::entities::install_status::InstallStatus;
+use crate::handlers::socket_client;
+use crate::network::network_manager;
+use crate::network::file_transfer::FileTransferState;
 
 // Define Rust structures to represent the YAML sections
 #[derive(Debug, Deserialize)]
@@ -45,93 +53,147 @@ enum InstallerStep {
     Chmodx { file: String },
 }
 
-// test function to parse the YAML file and execute the steps
-fn test() {
-    let yaml_path = "example_lutris.yaml"; // Replace with your YAML path
-    let yaml_content = fs::read_to_string(yaml_path).expect("Failed to read YAML file");
+impl InstallerStep {
+    fn label(&self) -> &'static str {
+        match self {
+            InstallerStep::Download { .. } => "download",
+            InstallerStep::Move { .. } => "move",
+            InstallerStep::Execute { .. } => "execute",
+            InstallerStep::Extract { .. } => "extract",
+            InstallerStep::Chmodx { .. } => "chmodx",
+        }
+    }
+}
+
+/// Parses a Lutris-style YAML installer script and runs its steps in order,
+/// pushing an `InstallStatus` to connected websocket clients after each one.
+/// Stops and reports an error on the first step that fails, rather than
+/// panicking like the shelling-out prototype used to.
+pub async fn run_install(yaml_path: &str) -> Result<(), DillingerError> {
+    let yaml_content = fs::read_to_string(yaml_path).map_err(|e| DillingerError {
+        description: format!("Failed to read Lutris script {}: {:?}", yaml_path, e),
+    })?;
+
+    let lutris_script: LutrisScript =
+        serde_yaml::from_str(&yaml_content).map_err(|e| DillingerError {
+            description: format!("Failed to parse Lutris script {}: {:?}", yaml_path, e),
+        })?;
 
-    let lutris_script: LutrisScript = serde_yaml::from_str(&yaml_content)
-        .expect("Failed to parse YAML file");
+    info!("Running Lutris install for {}", lutris_script.name);
 
-    println!("Parsed Lutris Script: {:?}", lutris_script);
+    let total_steps = lutris_script.script.installer.len();
+    for (index, step) in lutris_script.script.installer.into_iter().enumerate() {
+        let label = step.label();
+        let progress = ((index as f64 / total_steps.max(1) as f64) * 100.0) as u8;
 
-    // Process the installer steps
-    for step in lutris_script.script.installer {
-        execute_step(step);
+        if let Err(e) = execute_step(step).await {
+            send_status(InstallStatus::failed(label, e.description.clone())).await;
+            return Err(e);
+        }
+
+        let status = InstallStatus::new(label, progress, &format!("Completed step: {}", label));
+        send_status(status).await;
     }
+
+    send_status(InstallStatus::finished(&lutris_script.name)).await;
+    Ok(())
 }
 
-// Function to execute individual installer steps
-fn execute_step(step: InstallerStep) {
+async fn send_status(status: InstallStatus) {
+    let json_payload = serde_json::to_string(&status).unwrap();
+    socket_client::send_message(json_payload).await;
+}
+
+// Runs an individual installer step, returning a `DillingerError` instead of
+// panicking so a bad install script can't take the server down with it.
+async fn execute_step(step: InstallerStep) -> Result<(), DillingerError> {
     match step {
-        InstallerStep::Download { file, dst } => download_file(&file, &dst),
-        InstallerStep::Move { src, dst } => move_file(&src, &dst),
-        InstallerStep::Execute { command, args } => execute_command(&command, args),
+        InstallerStep::Download { file, dst } => download_file(&file, &dst).await,
+        InstallerStep::Move { src, dst } => move_file(&src, &dst).await,
+        InstallerStep::Execute { command, args } => execute_command(&command, args).await,
         InstallerStep::Extract { file, dst } => extract_archive(&file, &dst),
-        InstallerStep::Chmodx { file } => make_executable(&file),
+        InstallerStep::Chmodx { file } => make_executable(&file).await,
     }
 }
 
-// Function to download a file using wget
-fn download_file(url: &str, destination: &str) {
-    println!("Downloading file from {} to {}", url, destination);
-    let status = Command::new("wget")
-        .arg(url)
-        .arg("-O")
-        .arg(destination)
-        .status()
-        .expect("Failed to execute wget");
-    if !status.success() {
-        panic!("Download failed");
+// Downloads via the existing FileTransfer path instead of shelling out to wget.
+async fn download_file(url: &str, destination: &str) -> Result<(), DillingerError> {
+    debug!("Downloading {} to installer file {}", url, destination);
+    let remote_url = url.to_string();
+    let local_path = std::path::PathBuf::from(destination);
+
+    let transfer_id = network_manager::add_file_transfer(remote_url.clone(), local_path)
+        .await
+        .ok_or_else(|| DillingerError {
+            description: format!("Refusing to download {} - server is shutting down", remote_url),
+        })?;
+    network_manager::start_file_transfer(transfer_id, remote_url.clone()).await;
+
+    let ft_map = network_manager::acquire_file_transfers_map().await;
+    match ft_map.get(&transfer_id).map(|ft| ft.status.clone()) {
+        Some(status) if status.state == FileTransferState::Failed => Err(DillingerError {
+            description: format!("Failed to download {}: {}", remote_url, status.reason),
+        }),
+        _ => Ok(()),
     }
 }
 
-// Function to move a file
-fn move_file(src: &str, dst: &str) {
-    println!("Moving file from {} to {}", src, dst);
-    fs::rename(src, dst).expect("Failed to move file");
+async fn move_file(src: &str, dst: &str) -> Result<(), DillingerError> {
+    debug!("Moving file from {} to {}", src, dst);
+    tokio::fs::rename(src, dst).await.map_err(|e| DillingerError {
+        description: format!("Failed to move {} to {}: {:?}", src, dst, e),
+    })
 }
 
-// Function to execute a command
-fn execute_command(command: &str, args: Option<String>) {
-    println!("Executing command: {} {}", command, args.clone().unwrap_or_default());
-    let status = if let Some(arguments) = args {
-        Command::new(command)
-            .args(arguments.split_whitespace())
-            .status()
-            .expect("Failed to execute command")
+async fn execute_command(command: &str, args: Option<String>) -> Result<(), DillingerError> {
+    debug!("Executing command: {} {}", command, args.clone().unwrap_or_default());
+    let mut cmd = Command::new(command);
+    if let Some(arguments) = args {
+        cmd.args(arguments.split_whitespace());
+    }
+
+    let status = cmd.status().await.map_err(|e| DillingerError {
+        description: format!("Failed to execute {}: {:?}", command, e),
+    })?;
+
+    if status.success() {
+        Ok(())
     } else {
-        Command::new(command).status().expect("Failed to execute command")
-    };
-    if !status.success() {
-        panic!("Command execution failed");
+        Err(DillingerError {
+            description: format!("Command {} exited with status {:?}", command, status.code()),
+        })
     }
 }
 
-// Function to extract an archive
-fn extract_archive(file: &str, destination: &str) {
-    println!("Extracting archive {} to {}", file, destination);
-    let status = Command::new("tar")
-        .arg("-xvf")
-        .arg(file)
-        .arg("-C")
-        .arg(destination)
-        .status()
-        .expect("Failed to execute tar");
-    if !status.success() {
-        panic!("Extraction failed");
-    }
+// Extraction happens in-process rather than shelling out to tar.
+fn extract_archive(file: &str, destination: &str) -> Result<(), DillingerError> {
+    debug!("Extracting archive {} to {}", file, destination);
+    let tar_gz = fs::File::open(file).map_err(|e| DillingerError {
+        description: format!("Failed to open archive {}: {:?}", file, e),
+    })?;
+    let tar = GzDecoder::new(tar_gz);
+    let mut archive = Archive::new(tar);
+    archive.unpack(Path::new(destination)).map_err(|e| DillingerError {
+        description: format!("Failed to extract {} to {}: {:?}", file, destination, e),
+    })
 }
 
-// Function to make a file executable
-fn make_executable(file: &str) {
-    println!("Making file {} executable", file);
+async fn make_executable(file: &str) -> Result<(), DillingerError> {
+    debug!("Making file {} executable", file);
     let status = Command::new("chmod")
         .arg("+x")
         .arg(file)
         .status()
-        .expect("Failed to execute chmod");
-    if !status.success() {
-        panic!("Chmodx failed");
+        .await
+        .map_err(|e| DillingerError {
+            description: format!("Failed to chmod {}: {:?}", file, e),
+        })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(DillingerError {
+            description: format!("chmod +x {} exited with status {:?}", file, status.code()),
+        })
     }
 }
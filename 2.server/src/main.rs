@@ -1,28 +1,33 @@
 #[macro_use]
 extern crate lazy_static;
 
+use crate::core::{with_core, DillingerCore};
 use crate::error_response::ErrorResponse;
-use crate::game_manager::GameCacheEntries;
-use crate::handlers::docker_interactor::DockerContainer;
+use crate::docker::docker_interactor::DockerContainer;
 use crate::input::udev;
 
 use config::MasterConfig;
 use entities::game::Game;
-use env_logger;
+use gamedb::gamedb::GameDbGameEntry;
 use gamedb::gamedb_search;
-use log::info;
 use network::network_manager;
+use request_tracing::{request_id, with_request_id_header};
+use serde::Deserialize;
 use std::convert::Infallible;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
+use tracing::{info, Instrument};
 use urlencoding::decode;
+use utoipa::ToSchema;
 use warp::cors;
 use warp::http::StatusCode;
 use warp::reply::{json, with_status};
 use warp::Filter; // For global initialization
 
+pub mod cluster;
 pub mod config;
+pub mod core;
 pub mod docker;
 pub mod entities;
 pub mod error_response;
@@ -34,30 +39,47 @@ pub mod handlers;
 pub mod helpers;
 pub mod input;
 pub mod lutris;
+pub mod manifest_manager;
+pub mod media_store;
 pub mod network;
+pub mod openapi;
 pub mod platform;
+pub mod request_tracing;
 pub mod scrapers;
+pub mod shutdown;
+pub mod storage;
 pub mod system;
 
 // tests
 pub mod tests;
 
 lazy_static! {
-    // Find, load, and parse the master config file. This will panic if things aren't
-    // correct. Nothing works without it; there is no graceful fallback
-    static ref GLOBAL_CONFIG: Arc<MasterConfig> = config::get_master_config();
-    static ref GAME_CACHE: Arc<Mutex<GameCacheEntries>> = Arc::new(Mutex::new(GameCacheEntries::from(Vec::new())));
+    // Find, load, and parse the master config file. This will panic if things
+    // aren't correct on startup; there is no graceful fallback for that. Once
+    // running, `config::watch_for_config_changes` keeps this up to date so
+    // readers calling `.load()` see edits without a restart.
+    static ref GLOBAL_CONFIG: arc_swap::ArcSwap<MasterConfig> =
+        arc_swap::ArcSwap::new(config::get_master_config());
 }
 
 #[tokio::main]
 pub async fn main() {
-    env_logger::init();
+    tracing_subscriber::fmt::init();
 
-    match GLOBAL_CONFIG.root_dir.canonicalize() {
+    match GLOBAL_CONFIG.load().root_dir.canonicalize() {
         Ok(absolute_path) => println!("Absolute path is {:?}", absolute_path),
         Err(e) => println!("Error resolving absolute path: {}", e),
     }
 
+    // Watch dillinger_config.toml for edits so the server can pick up changes
+    // like `platforms` without a restart. The listening `port` is bound once
+    // below, so a `port` change takes effect only after a restart.
+    config::watch_for_config_changes(&GLOBAL_CONFIG);
+
+    // The core service - owns the config, game cache and docker pool so
+    // handlers can take it as an argument instead of reaching for globals.
+    let core = DillingerCore::new(GLOBAL_CONFIG.load_full()).await;
+
     // Set up path handlers
     let root = warp::path!().map(|| "You shouldn't have come back, Flynn.");
 
@@ -65,37 +87,77 @@ pub async fn main() {
     let ping_handler = warp::path!("diag" / "ping").and_then(diagnostics_ping_handler);
 
     // Docker status route - used for diagnostics
-    let docker_status_handler =
-        warp::path!("diag" / "docker_status").and_then(diagnostics_docker_status_handler);
+    let docker_status_handler = warp::path!("diag" / "docker_status")
+        .and(with_core(core.clone()))
+        .and(request_id())
+        .and_then(diagnostics_docker_status_handler);
+
+    // Docker connection pool stats - used for diagnostics and sizing
+    let docker_pool_handler = warp::path!("diag" / "docker_pool")
+        .and(request_id())
+        .and_then(diagnostics_docker_pool_handler);
 
     // Get a list of running containers
-    let docker_list_containers_handler =
-        warp::path!("sys" / "list_containers").and_then(handler_list_containers);
+    let docker_list_containers_handler = warp::path!("sys" / "list_containers")
+        .and(with_core(core.clone()))
+        .and(request_id())
+        .and_then(handler_list_containers);
 
     // Get a list of docker volumes
-    let docker_list_volumes_handler = warp::path!("sys" / "volumes").and_then(handler_list_volumes);
+    let docker_list_volumes_handler = warp::path!("sys" / "volumes")
+        .and(with_core(core.clone()))
+        .and(request_id())
+        .and_then(handler_list_volumes);
 
     // Get the directory contents from the specified path
-    let list_directory_contents =
-        warp::path!("sys" / "ls" / String).and_then(handler_list_directory_contents);
+    let list_directory_contents = warp::path!("sys" / "ls" / String)
+        .and(request_id())
+        .and_then(handler_list_directory_contents);
 
     // Game Management
-    let build_game_cache =
-        warp::path!("mgmt" / "build_game_cache").and_then(handler_build_game_cache);
+    let build_game_cache = warp::path!("mgmt" / "build_game_cache")
+        .and(with_core(core.clone()))
+        .and(request_id())
+        .and_then(handler_build_game_cache);
 
     // Search local entries
-    let search_local = warp::path!("search" / "local" / String).and_then(handler_search_local);
+    let search_local = warp::path!("search" / "local" / String)
+        .and(with_core(core.clone()))
+        .and(request_id())
+        .and_then(handler_search_local);
 
     // Get local entry by slug
-    let slug_local = warp::path!("slug" / "local" / String).and_then(handler_slug_local);
+    let slug_local = warp::path!("slug" / "local" / String)
+        .and(with_core(core.clone()))
+        .and(request_id())
+        .and_then(handler_slug_local);
+
+    // Search local entries plus every configured peer's local entries
+    let search_cluster = warp::path!("search" / "cluster" / String)
+        .and(with_core(core.clone()))
+        .and(request_id())
+        .and_then(handler_search_cluster);
 
     // Search remote entries
-    let search_remote =
-        warp::path!("search" / "remote" / String / String).and_then(handler_search_remote);
+    let search_remote = warp::path!("search" / "remote" / String / String)
+        .and(warp::query::<RefreshQuery>())
+        .and(request_id())
+        .and_then(handler_search_remote);
 
     // Get details for a specific title
-    let game_details =
-        warp::path!("game" / "remote" / String / String).and_then(handler_get_game_details);
+    let game_details = warp::path!("game" / "remote" / String / String)
+        .and(warp::query::<RefreshQuery>())
+        .and(request_id())
+        .and_then(handler_get_game_details);
+
+    // Serves a screenshot/cover-art blob previously `put` into the MediaStore,
+    // at the same path `MediaStore::url_for` hands back.
+    let media_get = warp::path!("media" / String)
+        .and(with_core(core.clone()))
+        .and_then(handler_get_media);
+
+    // OpenAPI spec + Swagger UI for the scraping routes above
+    let api_docs = openapi::routes();
 
     let ws_route = warp::path("ws")
         .and(warp::ws())
@@ -107,28 +169,36 @@ pub async fn main() {
     let routes = root
         .or(ping_handler)
         .or(docker_status_handler)
+        .or(docker_pool_handler)
         .or(docker_list_containers_handler)
         .or(docker_list_volumes_handler)
         .or(list_directory_contents)
         .or(search_local)
+        .or(search_cluster)
         .or(search_remote)
         .or(game_details)
         .or(slug_local)
         .or(build_game_cache)
+        .or(media_get)
+        .or(api_docs)
         .or(ws_route)
         .with(cors().allow_any_origin());
 
     // Prime the local search cache
     {
-        let mut cache: MutexGuard<GameCacheEntries> = GAME_CACHE.lock().unwrap();
+        let mut cache = core.game_cache.write().await;
         cache.update(
-            game_manager::prime_game_cache(0, GLOBAL_CONFIG.clone())
+            game_manager::prime_game_cache(0, GLOBAL_CONFIG.load_full())
                 .await
                 .unwrap()
                 .entries,
         )
     } // Cache lock will go out of scope and unlock here
 
+    // Resume any file transfers that were still in-flight last time the
+    // server stopped.
+    network_manager::restore_persisted_transfers().await;
+
     // // Spawn a task to send messages to clients every 1 second
     tokio::spawn(async move {
         network_manager::start_file_transfers().await;
@@ -180,11 +250,16 @@ pub async fn main() {
     // Start the engine
     println!(
         "Dillinger server is running on port: {}",
-        GLOBAL_CONFIG.port
+        GLOBAL_CONFIG.load().port
     );
-    warp::serve(routes)
-        .run(([0, 0, 0, 0], GLOBAL_CONFIG.port))
-        .await;
+
+    let (_, server) = warp::serve(routes).bind_with_graceful_shutdown(
+        ([0, 0, 0, 0], GLOBAL_CONFIG.load().port),
+        shutdown::wait_for_shutdown_signal(),
+    );
+    server.await;
+
+    shutdown::run_shutdown_tasks(core).await;
 }
 
 // fn with_clients(
@@ -193,18 +268,26 @@ pub async fn main() {
 //     warp::any().map(move || clients.clone())
 // }
 
-async fn handler_build_game_cache() -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: handler_build_game_cache");
+async fn handler_build_game_cache(
+    core: Arc<DillingerCore>,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: handler_build_game_cache");
+    let span = tracing::info_span!("build_game_cache", request_id = %request_id);
     tokio::task::spawn({
-        let config = Arc::clone(&GLOBAL_CONFIG);
+        let config = Arc::clone(&core.config);
         async move {
             let _ = game_manager::build_game_cache(config).await;
         }
+        .instrument(span)
     });
 
-    Ok(warp::reply::with_status(
-        warp::reply::json(&serde_json::json!({ "result": "build_game_cache requested" })),
-        StatusCode::OK,
+    Ok(with_request_id_header(
+        &request_id,
+        warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "result": "build_game_cache requested" })),
+            StatusCode::OK,
+        ),
     ))
 }
 
@@ -215,62 +298,117 @@ async fn diagnostics_ping_handler() -> Result<impl warp::Reply, Infallible> {
     Ok(warp::reply::with_status(status, StatusCode::OK))
 }
 
+/// Handler for the diagnostics docker pool route
+async fn diagnostics_docker_pool_handler(
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: diagnostics_docker_pool");
+    let status = docker::pool::status().await;
+    Ok(with_request_id_header(
+        &request_id,
+        warp::reply::with_status(warp::reply::json(&status), StatusCode::OK),
+    ))
+}
+
 /// Handler for the diagnostics docker status route
-async fn diagnostics_docker_status_handler() -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: diagnostics_docker_status");
-    let status = handlers::docker_interactor::get_docker_daemon_status().await;
-    info!("got a status back");
-    Ok(warp::reply::with_status(
-        warp::reply::json(&status),
-        StatusCode::OK,
+async fn diagnostics_docker_status_handler(
+    core: Arc<DillingerCore>,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: diagnostics_docker_status");
+    let status = core.runtime.get_daemon_status().await;
+    info!(request_id = %request_id, "got a status back");
+    Ok(with_request_id_header(
+        &request_id,
+        warp::reply::with_status(warp::reply::json(&status), StatusCode::OK),
     ))
 }
 
-async fn handler_list_containers() -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: running_containers");
-    let containers = handlers::docker_interactor::list_running_containers().await;
-    match containers {
-        Ok(containers) => Ok(warp::reply::with_status(
-            warp::reply::json(&containers),
-            StatusCode::OK,
-        )),
-        Err(_) => Ok(warp::reply::with_status(
+async fn handler_list_containers(
+    core: Arc<DillingerCore>,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: running_containers");
+    let containers = core.runtime.list_running_containers().await;
+    let reply = match containers {
+        Ok(containers) => warp::reply::with_status(warp::reply::json(&containers), StatusCode::OK),
+        Err(_) => warp::reply::with_status(
             warp::reply::json(&Vec::<DockerContainer>::new()),
             StatusCode::INTERNAL_SERVER_ERROR,
-        )),
-    }
+        ),
+    };
+    Ok(with_request_id_header(&request_id, reply))
 }
 
-async fn handler_list_volumes() -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: list_volumes");
-    let volumes = docker::docker_interactor::list_named_volumes().await;
-    info!("volumes: {:?}", volumes);
+async fn handler_list_volumes(
+    core: Arc<DillingerCore>,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: list_volumes");
+    let volumes = core.runtime.list_named_volumes().await;
+    info!(request_id = %request_id, "volumes: {:?}", volumes);
     match volumes {
-        Ok(volumes) => Ok(warp::reply::with_status(
-            warp::reply::json(&volumes),
-            StatusCode::OK,
+        Ok(volumes) => Ok(with_request_id_header(
+            &request_id,
+            warp::reply::with_status(warp::reply::json(&volumes), StatusCode::OK).into_response(),
         )),
         Err(e) => {
             let error_response: ErrorResponse = e.into();
-            Ok(with_status(
-                json(&error_response),
-                StatusCode::INTERNAL_SERVER_ERROR,
+            Ok(with_request_id_header(
+                &request_id,
+                with_status(json(&error_response), StatusCode::INTERNAL_SERVER_ERROR)
+                    .into_response(),
             ))
         }
     }
 }
 
-async fn handler_list_directory_contents(path: String) -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: handler_list_directory_contents");
+/// Serves a blob previously `put` into the `MediaStore` - the read half of
+/// the screenshot/cover-art pipeline `screenshot_queue` writes into.
+async fn handler_get_media(
+    id: String,
+    core: Arc<DillingerCore>,
+) -> Result<impl warp::Reply, Infallible> {
+    let media_id = match media_store::MediaId::parse(&id) {
+        Some(media_id) => media_id,
+        None => {
+            return Ok(warp::reply::with_status(Vec::new(), StatusCode::BAD_REQUEST).into_response())
+        }
+    };
+
+    let bytes = match core.media_store.get(&media_id).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return Ok(warp::reply::with_status(Vec::new(), StatusCode::NOT_FOUND).into_response())
+        }
+    };
+
+    let content_type = core
+        .media_store
+        .content_type(&media_id)
+        .await
+        .unwrap_or_else(|_| "application/octet-stream".to_string());
+
+    Ok(warp::reply::with_header(bytes, "Content-Type", content_type).into_response())
+}
+
+async fn handler_list_directory_contents(
+    path: String,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: handler_list_directory_contents");
     let decoded_path = decode(&path).unwrap().into_owned();
     let contents = files::filesystem::get_directory_contents(decoded_path).await;
-    info!("contents: {:?}", contents);
+    info!(request_id = %request_id, "contents: {:?}", contents);
     match contents {
-        Ok(contents) => Ok(warp::reply::with_status(
-            warp::reply::json(&contents),
-            StatusCode::OK,
+        Ok(contents) => Ok(with_request_id_header(
+            &request_id,
+            warp::reply::with_status(warp::reply::json(&contents), StatusCode::OK).into_response(),
+        )),
+        Err(e) => Ok(with_request_id_header(
+            &request_id,
+            with_status(json(&e), StatusCode::INTERNAL_SERVER_ERROR).into_response(),
         )),
-        Err(e) => Ok(with_status(json(&e), StatusCode::INTERNAL_SERVER_ERROR)),
     }
 }
 
@@ -290,9 +428,13 @@ async fn handler_list_directory_contents(path: String) -> Result<impl warp::Repl
 //     }
 // }
 
-async fn handler_search_local(search_term: String) -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: search_local");
-    let cache: MutexGuard<GameCacheEntries> = GAME_CACHE.lock().unwrap();
+async fn handler_search_local(
+    search_term: String,
+    core: Arc<DillingerCore>,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: search_local");
+    let cache = core.game_cache.read().await;
 
     let results = cache
         .entries
@@ -300,15 +442,19 @@ async fn handler_search_local(search_term: String) -> Result<impl warp::Reply, I
         .filter(|entry| entry.slug.contains(&search_term))
         .collect::<Vec<&game_manager::GameCacheEntry>>();
 
-    Ok(warp::reply::with_status(
-        warp::reply::json(&results),
-        StatusCode::OK,
+    Ok(with_request_id_header(
+        &request_id,
+        warp::reply::with_status(warp::reply::json(&results), StatusCode::OK),
     ))
 }
 
-async fn handler_slug_local(slug: String) -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: slug_local");
-    let cache: MutexGuard<GameCacheEntries> = GAME_CACHE.lock().unwrap();
+async fn handler_slug_local(
+    slug: String,
+    core: Arc<DillingerCore>,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: slug_local");
+    let cache = core.game_cache.read().await;
 
     let results = cache
         .entries
@@ -316,60 +462,110 @@ async fn handler_slug_local(slug: String) -> Result<impl warp::Reply, Infallible
         .filter(|entry| entry.slug.contains(&slug))
         .collect::<Vec<&game_manager::GameCacheEntry>>();
 
-    Ok(warp::reply::with_status(
-        warp::reply::json(&results),
-        StatusCode::OK,
+    Ok(with_request_id_header(
+        &request_id,
+        warp::reply::with_status(warp::reply::json(&results), StatusCode::OK),
     ))
 }
 
+async fn handler_search_cluster(
+    search_term: String,
+    core: Arc<DillingerCore>,
+    request_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    info!(request_id = %request_id, "route requested: search_cluster");
+    let results = cluster::search_cluster(&core, &search_term).await;
+
+    Ok(with_request_id_header(
+        &request_id,
+        warp::reply::with_status(warp::reply::json(&results), StatusCode::OK),
+    ))
+}
+
+/// `?refresh=true` forces a live fetch, bypassing the on-disk search cache.
+#[derive(Debug, Deserialize, ToSchema)]
+struct RefreshQuery {
+    #[serde(default)]
+    refresh: bool,
+}
+
+/// Searches `search_db` (or a comma-separated list, or `all`) for
+/// `search_term`.
+#[utoipa::path(
+    get,
+    path = "/search/remote/{search_db}/{search_term}",
+    params(
+        ("search_db" = String, Path, description = "Provider name, comma-separated names, or `all`"),
+        ("search_term" = String, Path, description = "Title to search for"),
+        RefreshQuery,
+    ),
+    responses(
+        (status = 200, description = "Matching titles", body = [GameDbGameEntry]),
+        (status = 400, description = "Unknown search_db or no providers enabled", body = ErrorResponse),
+    ),
+    tag = "gamedb",
+)]
 async fn handler_search_remote(
     search_db: String,
     search_term: String,
+    refresh: RefreshQuery,
+    request_id: String,
 ) -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: search_remote");
-    info!("search db: {}", search_db);
-    info!("search term: {}", search_term);
-
-    let mut results = vec![];
+    info!(request_id = %request_id, "route requested: search_remote");
+    info!(request_id = %request_id, "search db: {}", search_db);
+    info!(request_id = %request_id, "search term: {}", search_term);
 
-    let matching_titles = gamedb_search::search_title(search_db, search_term).await;
-    match matching_titles {
-        Ok(titles) => {
-            results = titles;
-        }
+    let matching_titles = gamedb_search::search_title(search_db, search_term, refresh.refresh).await;
+    let reply = match matching_titles {
+        Ok(titles) => with_status(json(&titles), StatusCode::OK),
         Err(e) => {
-            info!("Error searching remote: {}", e.description);
+            info!(request_id = %request_id, "Error searching remote: {}", e.description);
+            with_status(
+                json(&ErrorResponse::new(e.description)),
+                StatusCode::BAD_REQUEST,
+            )
         }
-    }
+    };
 
-    Ok(warp::reply::with_status(
-        warp::reply::json(&results),
-        StatusCode::OK,
-    ))
+    Ok(with_request_id_header(&request_id, reply))
 }
 
+/// Fetches full game details for `game_slug` from `search_db`.
+#[utoipa::path(
+    get,
+    path = "/game/remote/{search_db}/{game_slug}",
+    params(
+        ("search_db" = String, Path, description = "Provider name to fetch details from"),
+        ("game_slug" = String, Path, description = "Slug returned by search_title"),
+        RefreshQuery,
+    ),
+    responses(
+        (status = 200, description = "Game details", body = Game),
+        (status = 400, description = "Unknown search_db or slug not found", body = ErrorResponse),
+    ),
+    tag = "gamedb",
+)]
 async fn handler_get_game_details(
     search_db: String,
     game_slug: String,
+    refresh: RefreshQuery,
+    request_id: String,
 ) -> Result<impl warp::Reply, Infallible> {
-    info!("route requested: get_game_details");
-    info!("search db: {}", search_db);
-    info!("game slug: {}", game_slug);
-
-    let mut game = Game::new();
+    info!(request_id = %request_id, "route requested: get_game_details");
+    info!(request_id = %request_id, "search db: {}", search_db);
+    info!(request_id = %request_id, "game slug: {}", game_slug);
 
-    let matching_game = gamedb_search::get_game_details(search_db, game_slug).await;
-    match matching_game {
-        Ok(found_game) => {
-            game = found_game.clone();
-        }
+    let matching_game = gamedb_search::get_game_details(search_db, game_slug, refresh.refresh).await;
+    let reply = match matching_game {
+        Ok(found_game) => with_status(json(&found_game), StatusCode::OK),
         Err(e) => {
-            info!("Error searching remote: {}", e.description);
+            info!(request_id = %request_id, "Error searching remote: {}", e.description);
+            with_status(
+                json(&ErrorResponse::new(e.description)),
+                StatusCode::BAD_REQUEST,
+            )
         }
-    }
+    };
 
-    Ok(warp::reply::with_status(
-        warp::reply::json(&game),
-        StatusCode::OK,
-    ))
+    Ok(with_request_id_header(&request_id, reply))
 }
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::config::MasterConfig;
+use crate::entities::game::Game;
+use crate::entities::playstats::PlayStats;
+use crate::entities::save_backup::SaveBackup;
+
+lazy_static! {
+    /// One mutex per manifest path, so concurrent writers (scrape activity,
+    /// play-session tracking, save backups) serialize their read-modify-write
+    /// instead of racing and clobbering each other's appends.
+    static ref MANIFEST_LOCKS: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> = Mutex::new(HashMap::new());
+}
+
+/// Reads and writes a single game's JSON manifest under its folder in
+/// `root_dir`, e.g. `{root_dir}/{slug}/{slug}.json`.
+pub struct ManifestManager;
+
+impl ManifestManager {
+    fn manifest_path(config: &MasterConfig, slug: &str) -> PathBuf {
+        config.root_dir.join(slug).join(format!("{}.json", slug))
+    }
+
+    /// The directory all per-game folders live under, e.g. the parent of
+    /// `{root_dir}/{slug}/`. Exposed so callers that need to lay out files
+    /// alongside a game's manifest (like save backups) don't have to reach
+    /// into `MasterConfig` directly.
+    pub fn games_get_parent_dir(config: &MasterConfig) -> PathBuf {
+        config.root_dir.clone()
+    }
+
+    /// Where a game's save backups are kept: `{root_dir}/{slug}/saves/`.
+    pub fn saves_dir(config: &MasterConfig, slug: &str) -> PathBuf {
+        Self::games_get_parent_dir(config).join(slug).join("saves")
+    }
+
+    async fn lock_for(path: &PathBuf) -> Arc<Mutex<()>> {
+        let mut locks = MANIFEST_LOCKS.lock().await;
+        locks.entry(path.clone()).or_insert_with(|| Arc::new(Mutex::new(()))).clone()
+    }
+
+    /// Reads the manifest at `path`, applies `mutate` to the deserialized
+    /// `Game`, and writes the result back atomically - serialized to a temp
+    /// file in the same directory then `rename`d over the target, so a crash
+    /// mid-write can never leave a half-written manifest behind. Guarded by a
+    /// per-path lock so two concurrent callers can't interleave their
+    /// read-modify-write and silently drop one another's appends.
+    async fn update_game(
+        path: &PathBuf,
+        mutate: impl FnOnce(&mut Game),
+    ) -> Result<(), Box<dyn Error>> {
+        let lock = Self::lock_for(path).await;
+        let _guard = lock.lock().await;
+
+        let contents = tokio::fs::read_to_string(path).await?;
+        let mut game: Game = serde_json::from_str(&contents)?;
+        mutate(&mut game);
+        let serialized = serde_json::to_string_pretty(&game)?;
+
+        let temp_name = format!("{}.tmp", path.file_name().unwrap().to_string_lossy());
+        let temp_path = path.with_file_name(temp_name);
+
+        tokio::fs::write(&temp_path, serialized).await?;
+        tokio::fs::rename(&temp_path, path).await?;
+        Ok(())
+    }
+
+    /// Appends a completed save backup to a game's manifest, so the UI can
+    /// list and restore prior backups.
+    pub async fn record_save_backup(
+        config: &Arc<MasterConfig>,
+        slug: &str,
+        archive_path: PathBuf,
+    ) -> Result<(), Box<dyn Error>> {
+        let manifest_path = Self::manifest_path(config, slug);
+        Self::update_game(&manifest_path, |game| {
+            game.save_backups.get_or_insert_with(Vec::new).push(SaveBackup {
+                timestamp: chrono::Utc::now(),
+                archive_path,
+            });
+        })
+        .await
+    }
+
+    /// Appends a completed play session to a game's manifest and updates
+    /// `last_played`, so "last played"/total-playtime data stays accurate
+    /// without polling the container.
+    pub async fn add_play_session(
+        config: &Arc<MasterConfig>,
+        slug: &str,
+        session: PlayStats,
+    ) -> Result<(), Box<dyn Error>> {
+        let manifest_path = Self::manifest_path(config, slug);
+        Self::update_game(&manifest_path, |game| {
+            game.last_played = session.time_played;
+            game.play_stats.get_or_insert_with(Vec::new).push(session);
+        })
+        .await
+    }
+}
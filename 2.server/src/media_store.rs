@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use crate::config::MasterConfig;
+use crate::storage::{self, Store};
+
+/// Content hash identifying a blob in a `MediaStore` - two `put`s of
+/// identical bytes resolve to the same id, so e.g. the same screenshot
+/// referenced by two different games is only ever stored once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MediaId(String);
+
+impl MediaId {
+    /// Parses an id read from an untrusted source (e.g. a URL path segment),
+    /// rejecting anything that isn't a well-formed sha256 hex digest -
+    /// `key_for` shards on the first two hex-pair slices unconditionally, so
+    /// anything shorter than that would panic rather than 404.
+    pub fn parse(id: &str) -> Option<Self> {
+        if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            Some(MediaId(id.to_string()))
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Content-addressed storage for downloaded/scraped media (screenshots,
+/// cover art, ...), layered on top of `storage::Store` rather than the
+/// filesystem directly - so the same `File`/`Object` backend split already
+/// used for file transfers also dedups media, and an S3-backed deployment
+/// gets that for free without the scraper code knowing the difference.
+#[async_trait]
+pub trait MediaStore: Send + Sync {
+    /// Stores `bytes` and returns the `MediaId` it's addressed by. A no-op
+    /// if `bytes` is already stored under that id.
+    async fn put(&self, bytes: &[u8], content_type: &str) -> Result<MediaId, String>;
+
+    /// Whether `id` is already stored.
+    async fn exists(&self, id: &MediaId) -> bool;
+
+    /// Reads back the bytes stored under `id`.
+    async fn get(&self, id: &MediaId) -> Result<Vec<u8>, String>;
+
+    /// The content type `put` stored `id`'s bytes under, for the `/media/{id}`
+    /// route to answer with the right `Content-Type`.
+    async fn content_type(&self, id: &MediaId) -> Result<String, String>;
+
+    /// A URL the bytes stored under `id` can be served/fetched from.
+    fn url_for(&self, id: &MediaId) -> String;
+}
+
+/// The default `MediaStore`: content-addresses blobs by sha256 and delegates
+/// the actual bytes to whatever `storage::Store` the deployment is
+/// configured with.
+pub struct StoreMediaStore {
+    store: Arc<dyn Store>,
+}
+
+impl StoreMediaStore {
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        StoreMediaStore { store }
+    }
+
+    /// Shards by the first two hex pairs of the hash, so a large media
+    /// library doesn't land every blob in one giant flat directory.
+    fn key_for(&self, id: &MediaId) -> String {
+        let hash = id.as_str();
+        format!("media/{}/{}/{}", &hash[0..2], &hash[2..4], hash)
+    }
+
+    fn content_type_key(&self, id: &MediaId) -> String {
+        format!("{}.type", self.key_for(id))
+    }
+}
+
+#[async_trait]
+impl MediaStore for StoreMediaStore {
+    async fn put(&self, bytes: &[u8], content_type: &str) -> Result<MediaId, String> {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        let id = MediaId(format!("{:x}", hasher.finalize()));
+
+        if self.store.len(&self.key_for(&id)).await.is_none() {
+            self.store.write(&self.key_for(&id), bytes).await?;
+            self.store
+                .write(&self.content_type_key(&id), content_type.as_bytes())
+                .await?;
+        }
+        Ok(id)
+    }
+
+    async fn exists(&self, id: &MediaId) -> bool {
+        self.store.len(&self.key_for(id)).await.is_some()
+    }
+
+    async fn get(&self, id: &MediaId) -> Result<Vec<u8>, String> {
+        self.store.read_all(&self.key_for(id)).await
+    }
+
+    async fn content_type(&self, id: &MediaId) -> Result<String, String> {
+        let bytes = self.store.read_all(&self.content_type_key(id)).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn url_for(&self, id: &MediaId) -> String {
+        format!("/media/{}", id.as_str())
+    }
+}
+
+/// Opens the `MediaStore` layered on top of `storage::open`'s configured
+/// backend.
+pub fn open(config: &MasterConfig) -> Arc<dyn MediaStore> {
+    Arc::new(StoreMediaStore::new(storage::open(config)))
+}
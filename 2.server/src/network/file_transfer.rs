@@ -1,17 +1,17 @@
 use reqwest::Url;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::{fs::File, path::{Component, PathBuf}, time::Instant};
 use std::collections::HashMap;
 
-#[derive(Clone, Serialize, Debug, PartialEq)]
-pub enum FileTransferState { 
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum FileTransferState {
     NotStarted,
     InProgress,
     Completed,
     Failed
 }
 
-#[derive(Clone, Serialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct FileTransferStatus {
     pub state: FileTransferState,
     pub reason: String
@@ -26,7 +26,18 @@ impl FileTransferStatus {
     }
 }
 
-#[derive(Clone, Serialize, Debug)]
+/// One fixed-size byte range of a parallel multi-connection download -
+/// `start`/`end` are both inclusive, matching the `Range: bytes=start-end`
+/// header they're requested with. `done` lets a restart skip straight to
+/// re-requesting only the segments that never finished.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct TransferSegment {
+    pub start: u64,
+    pub end: u64,
+    pub done: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct FileTransfer {
     pub transfer_id: uuid::Uuid,
     pub remote_url: String,
@@ -36,7 +47,21 @@ pub struct FileTransfer {
     pub chunks_added_since: u64,
     pub transferred: u64,
     pub bandwidth: u128,
-    pub status: FileTransferStatus    
+    pub status: FileTransferStatus,
+    // Expected SHA-256 for the completed file, e.g. from a Redump/No-Intro DAT entry
+    pub expected_sha256: Option<String>,
+    // The request/trace ID that kicked this transfer off, so progress
+    // messages broadcast over the websocket can be correlated back to it
+    pub correlation_id: Option<String>,
+    // Populated when this transfer is large enough to be split across
+    // multiple connections - empty for a plain single-stream download.
+    pub segments: Vec<TransferSegment>,
+    // Epoch millis of the last byte received - lets a restart/summary tell a
+    // stalled transfer apart from an actively progressing one.
+    pub last_byte_at: u64,
+    // How many seconds have elapsed since `last_byte_at`, computed fresh each
+    // time `get_file_transfers_summary` is called.
+    pub idle_seconds: u64,
 }
 
 impl FileTransfer {
@@ -53,7 +78,12 @@ impl FileTransfer {
             status: FileTransferStatus {
                 state: FileTransferState::NotStarted,
                 reason: "Not Started".to_string()
-            }
+            },
+            expected_sha256: None,
+            correlation_id: None,
+            segments: Vec::new(),
+            last_byte_at: 0,
+            idle_seconds: 0,
         }
     }
 }
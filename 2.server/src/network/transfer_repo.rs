@@ -0,0 +1,210 @@
+use async_trait::async_trait;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use super::file_transfer::{FileTransfer, FileTransferState};
+
+/// Which persistence backend backs in-flight file transfers, configurable
+/// via `MasterConfig::transfer_repo_backend`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum TransferRepoBackend {
+    Sled,
+    Sqlite,
+}
+
+impl Default for TransferRepoBackend {
+    fn default() -> Self {
+        TransferRepoBackend::Sled
+    }
+}
+
+/// Abstracts away how in-flight `FileTransfer` rows are persisted, so a
+/// restart can re-enumerate transfers that were `InProgress` without the
+/// rest of `network_manager` caring which store backs it - mirrors
+/// `docker::runtime::ContainerRuntime`'s split between the trait and its
+/// concrete backend.
+#[async_trait]
+pub trait TransferRepo: Send + Sync {
+    /// Persists `ft`'s current progress so it survives a restart.
+    async fn save(&self, ft: &FileTransfer);
+
+    /// Removes a persisted transfer, e.g. once it's completed or cancelled.
+    async fn remove(&self, transfer_id: uuid::Uuid);
+
+    /// Loads every transfer that was in-flight when the server last stopped.
+    async fn load_all(&self) -> Vec<FileTransfer>;
+}
+
+/// The original embedded-KV backend, kept as the default so existing
+/// deployments don't need a migration to start persisting transfers again.
+const SLED_PATH: &str = "file_transfers.sled";
+
+pub struct SledTransferRepo {
+    db: sled::Db,
+}
+
+impl SledTransferRepo {
+    pub fn open() -> Self {
+        let db = sled::open(SLED_PATH)
+            .unwrap_or_else(|e| panic!("Could not open transfer store at {}: {:?}", SLED_PATH, e));
+        SledTransferRepo { db }
+    }
+}
+
+#[async_trait]
+impl TransferRepo for SledTransferRepo {
+    async fn save(&self, ft: &FileTransfer) {
+        match bincode::serialize(ft) {
+            Ok(encoded) => {
+                let _ = self.db.insert(ft.transfer_id.as_bytes(), encoded);
+            }
+            Err(e) => warn!("Failed to serialize file transfer {}: {:?}", ft.transfer_id, e),
+        }
+    }
+
+    async fn remove(&self, transfer_id: uuid::Uuid) {
+        let _ = self.db.remove(transfer_id.as_bytes());
+    }
+
+    async fn load_all(&self) -> Vec<FileTransfer> {
+        let mut transfers = Vec::new();
+        for entry in self.db.iter() {
+            let Ok((_, raw)) = entry else { continue };
+            match bincode::deserialize::<FileTransfer>(&raw) {
+                Ok(ft) if ft.status.state == FileTransferState::InProgress => transfers.push(ft),
+                Ok(_) => {}
+                Err(e) => debug!("Skipping unreadable persisted transfer: {:?}", e),
+            }
+        }
+        transfers
+    }
+}
+
+/// A SQLite-backed `TransferRepo`, pooled with `deadpool_sqlite` the way
+/// pict-rs pools its Postgres repo - rows survive a crash, and a restart can
+/// query `WHERE status = 'InProgress'` directly instead of deserializing
+/// every persisted entry.
+const SQLITE_PATH: &str = "file_transfers.db";
+
+pub struct SqliteTransferRepo {
+    pool: deadpool_sqlite::Pool,
+}
+
+impl SqliteTransferRepo {
+    pub async fn open() -> Self {
+        let pool = deadpool_sqlite::Config::new(SQLITE_PATH)
+            .create_pool(deadpool_sqlite::Runtime::Tokio1)
+            .unwrap_or_else(|e| panic!("Could not build sqlite connection pool: {:?}", e));
+
+        let repo = SqliteTransferRepo { pool };
+        repo.init_schema().await;
+        repo
+    }
+
+    async fn init_schema(&self) {
+        let conn = self.pool.get().await.expect("Could not check out a sqlite connection");
+        conn.interact(|conn| {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS file_transfers (
+                    transfer_id TEXT PRIMARY KEY,
+                    remote_url TEXT NOT NULL,
+                    local_file TEXT NOT NULL,
+                    size INTEGER NOT NULL,
+                    transferred INTEGER NOT NULL,
+                    status TEXT NOT NULL
+                )",
+                [],
+            )
+        })
+        .await
+        .expect("sqlite worker thread panicked")
+        .expect("Could not create file_transfers table");
+    }
+}
+
+#[async_trait]
+impl TransferRepo for SqliteTransferRepo {
+    async fn save(&self, ft: &FileTransfer) {
+        let transfer_id = ft.transfer_id.to_string();
+        let remote_url = ft.remote_url.clone();
+        let local_file = ft.local_file.to_string_lossy().to_string();
+        let size = ft.size as i64;
+        let transferred = ft.transferred as i64;
+        let status = format!("{:?}", ft.status.state);
+
+        let Ok(conn) = self.pool.get().await else {
+            warn!("Could not check out a sqlite connection to save transfer {}", transfer_id);
+            return;
+        };
+
+        let result = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "INSERT INTO file_transfers (transfer_id, remote_url, local_file, size, transferred, status)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                     ON CONFLICT(transfer_id) DO UPDATE SET
+                        remote_url = excluded.remote_url,
+                        local_file = excluded.local_file,
+                        size = excluded.size,
+                        transferred = excluded.transferred,
+                        status = excluded.status",
+                    rusqlite::params![transfer_id, remote_url, local_file, size, transferred, status],
+                )
+            })
+            .await;
+
+        if let Err(e) = result {
+            warn!("Failed to save file transfer {}: {:?}", ft.transfer_id, e);
+        }
+    }
+
+    async fn remove(&self, transfer_id: uuid::Uuid) {
+        let Ok(conn) = self.pool.get().await else {
+            warn!("Could not check out a sqlite connection to remove transfer {}", transfer_id);
+            return;
+        };
+
+        let id = transfer_id.to_string();
+        let _ = conn
+            .interact(move |conn| conn.execute("DELETE FROM file_transfers WHERE transfer_id = ?1", rusqlite::params![id]))
+            .await;
+    }
+
+    async fn load_all(&self) -> Vec<FileTransfer> {
+        let Ok(conn) = self.pool.get().await else {
+            warn!("Could not check out a sqlite connection to load persisted transfers");
+            return Vec::new();
+        };
+
+        conn.interact(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT transfer_id, remote_url, local_file, size, transferred, status FROM file_transfers WHERE status = 'InProgress'",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                let transfer_id: String = row.get(0)?;
+                let remote_url: String = row.get(1)?;
+                let local_file: String = row.get(2)?;
+                let size: i64 = row.get(3)?;
+                let transferred: i64 = row.get(4)?;
+
+                let mut ft = FileTransfer::new();
+                ft.transfer_id = uuid::Uuid::parse_str(&transfer_id).unwrap_or(ft.transfer_id);
+                ft.remote_url = remote_url;
+                ft.local_file = local_file.into();
+                ft.size = size as u64;
+                ft.transferred = transferred as u64;
+                ft.status = super::file_transfer::FileTransferStatus {
+                    state: super::file_transfer::FileTransferState::InProgress,
+                    reason: "".to_string(),
+                };
+                Ok(ft)
+            })?;
+
+            rows.collect::<Result<Vec<_>, rusqlite::Error>>()
+        })
+        .await
+        .ok()
+        .and_then(|r| r.ok())
+        .unwrap_or_default()
+    }
+}
@@ -0,0 +1,80 @@
+use utoipa::OpenApi;
+use utoipa_swagger_ui::Config;
+use std::sync::Arc;
+use warp::http::Uri;
+use warp::hyper::Response;
+use warp::path::{FullPath, Tail};
+use warp::{Filter, Rejection, Reply};
+
+use crate::entities::game::Game;
+use crate::entities::playstats::PlayStats;
+use crate::entities::save_backup::SaveBackup;
+use crate::error_response::ErrorResponse;
+use crate::gamedb::gamedb::GameDbGameEntry;
+use crate::platform::Platform;
+use crate::RefreshQuery;
+
+/// Describes the scraping API (remote title search and game detail lookup)
+/// so peer tools and the web client can discover it without reading the
+/// source - served as JSON at `/api-doc/openapi.json` and rendered by the
+/// Swagger UI route below.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        crate::handler_search_remote,
+        crate::handler_get_game_details,
+    ),
+    components(schemas(
+        GameDbGameEntry,
+        Game,
+        Platform,
+        PlayStats,
+        SaveBackup,
+        ErrorResponse,
+        RefreshQuery,
+    ))
+)]
+pub struct ApiDoc;
+
+/// Builds the `/api-doc/openapi.json` and `/swagger-ui/*` routes. `main`
+/// `.or()`s this into the rest of the warp route tree the same as any other
+/// handler.
+pub fn routes() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let openapi_json = warp::path!("api-doc" / "openapi.json")
+        .and(warp::get())
+        .map(|| warp::reply::json(&ApiDoc::openapi()));
+
+    let config = Arc::new(Config::from("/api-doc/openapi.json"));
+    let swagger_ui = warp::path("swagger-ui")
+        .and(warp::get())
+        .and(warp::path::full())
+        .and(warp::path::tail())
+        .and(warp::any().map(move || config.clone()))
+        .and_then(serve_swagger_ui);
+
+    openapi_json.or(swagger_ui)
+}
+
+async fn serve_swagger_ui(
+    full_path: FullPath,
+    tail: Tail,
+    config: Arc<Config<'static>>,
+) -> Result<Box<dyn Reply + 'static>, Rejection> {
+    if full_path.as_str() == "/swagger-ui" {
+        return Ok(Box::new(warp::redirect::found(Uri::from_static(
+            "/swagger-ui/",
+        ))));
+    }
+
+    let path = tail.as_str();
+    match utoipa_swagger_ui::serve(path, config) {
+        Ok(Some(file)) => Ok(Box::new(
+            Response::builder()
+                .header("Content-Type", file.content_type)
+                .body(file.bytes.to_vec())
+                .unwrap(),
+        )),
+        Ok(None) => Ok(Box::new(warp::http::StatusCode::NOT_FOUND)),
+        Err(_) => Ok(Box::new(warp::http::StatusCode::INTERNAL_SERVER_ERROR)),
+    }
+}
@@ -1,5 +1,5 @@
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, utoipa::ToSchema)]
 pub struct Platform {
     pub name: String,
     pub description: String,
@@ -0,0 +1,30 @@
+use std::convert::Infallible;
+
+use warp::http::HeaderValue;
+use warp::Filter;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Pulls a correlation ID off an inbound `X-Request-Id` or `traceparent`
+/// header, minting a fresh one if neither is present, so a single request
+/// can be traced from the HTTP route through to any async work it kicks off.
+pub fn request_id() -> impl Filter<Extract = (String,), Error = Infallible> + Clone {
+    warp::header::optional::<String>(REQUEST_ID_HEADER)
+        .and(warp::header::optional::<String>(TRACEPARENT_HEADER))
+        .map(|request_id: Option<String>, traceparent: Option<String>| {
+            request_id
+                .or(traceparent)
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+        })
+}
+
+/// Attaches the request's correlation ID to a reply so the client can
+/// correlate their own logs against ours too.
+pub fn with_request_id_header(request_id: &str, reply: impl warp::Reply) -> impl warp::Reply {
+    let mut response = reply.into_response();
+    if let Ok(value) = HeaderValue::from_str(request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
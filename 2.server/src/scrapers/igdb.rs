@@ -1,44 +1,67 @@
 use reqwest::header::{ HeaderMap, HeaderValue, ACCEPT, USER_AGENT };
 use async_trait::async_trait;
-use crate::scrapers::scrapers::{ AuthToken, GameDatabase, ScrapeEntry, ScreenshotInfo, PlatformEntry };
+use crate::scrapers::scrapers::{ AuthToken, GameDatabase, ScrapeEntry, ScreenshotInfo, PlatformEntry, THUMBNAIL_SIZES };
+use crate::network::file_transfer::FileTransferState;
+use crate::network::network_manager;
 
 
 pub struct IgdbDatabase {
     pub auth_token: AuthToken,
+    client_id: String,
+    client_secret: String,
+}
+
+impl IgdbDatabase {
+    /// Builds an `IgdbDatabase` from credentials resolved via
+    /// `scrapers::scrapers::IgdbCredentials::resolve` - see
+    /// `Scraper::get_scraper`.
+    pub fn new(client_id: String, client_secret: String) -> Self {
+        IgdbDatabase {
+            auth_token: AuthToken::new(),
+            client_id,
+            client_secret,
+        }
+    }
 }
 
 unsafe impl Send for IgdbDatabase {}
 
 #[async_trait]
 impl GameDatabase for IgdbDatabase {
-    
+
     async fn authentiate(&mut self) -> Result<AuthToken, reqwest::Error> {
+        if let Some(cached) = crate::scrapers::scrapers::cached_token("igdb") {
+            self.auth_token = cached.clone();
+            return Ok(cached);
+        }
+
         println!("Authenticating with IGDB");
 
-        let client_id = "lpzomulxapy5mrfftuxcnwidw5ob2q";
-        let client_secret = "me0k8eu07kdp2ayb5anxn05mvpzasb";
         let grant_type = "client_credentials";
 
         let url = format!(
             "https://id.twitch.tv/oauth2/token?client_id={}&client_secret={}&grant_type={}",
-            client_id,
-            client_secret,
+            self.client_id,
+            self.client_secret,
             grant_type
         );
         println!("URL: {}", url);
 
-        let client = reqwest::blocking::Client::new();
+        let client = reqwest::Client::new();
 
-        let res = client.post(url).send().unwrap().json::<serde_json::Value>();
+        let res = client.post(url).send().await?.json::<serde_json::Value>().await;
 
         match res {
             Ok(json) => {
-                Ok(AuthToken {
+                let token = AuthToken {
                     db: "igdb".to_string(),
                     access_token: json["access_token"].as_str().unwrap().to_string(),
                     expires_in: json["expires_in"].as_u64().unwrap(),
                     token_type: json["token_type"].as_str().unwrap().to_string(),
-                })
+                    issued_at: std::time::Instant::now(),
+                };
+                crate::scrapers::scrapers::cache_token(token.clone());
+                Ok(token)
             }
             Err(error) => {
                 println!("Error: {}", error);
@@ -48,13 +71,14 @@ impl GameDatabase for IgdbDatabase {
     }
 
     async fn search_game(&mut self, name: &str) -> Vec<ScrapeEntry> {
-        
+
         let token = self.authentiate().await;
 
         if token.is_err() {
             println!(
                 "Error authenticating with IGDB. You may need to check your configured credentials."
             );
+            return vec![];
         }
 
         let token = token.unwrap();
@@ -64,48 +88,83 @@ impl GameDatabase for IgdbDatabase {
 
         let url = format!("https://api.igdb.com/v4/games/?search={}&fields=id,name,slug", name);
 
-        let client = reqwest::blocking::Client::new();
+        let client = reqwest::Client::new();
         let mut headers = HeaderMap::new();
         headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
         headers.insert(USER_AGENT, HeaderValue::from_static("reqwest"));
 
-        let res = client
+        let mut res = client
             .post(&url)
-            .header("Client-ID", "lpzomulxapy5mrfftuxcnwidw5ob2q")
+            .header("Client-ID", self.client_id.clone())
             .header("Authorization", format!("Bearer {}", token.access_token))
-            .headers(headers)
+            .headers(headers.clone())
             .send()
-            .unwrap()
-            .json::<serde_json::Value>();
+            .await
+            .and_then(|response| response.error_for_status());
+
+        if let Err(error) = &res {
+            if error.status() == Some(reqwest::StatusCode::UNAUTHORIZED) {
+                println!("IGDB rejected our cached token as expired; re-authenticating once");
+                crate::scrapers::scrapers::invalidate_cached_token("igdb");
+                if let Ok(token) = self.authentiate().await {
+                    self.auth_token = token.clone();
+                    res = client
+                        .post(&url)
+                        .header("Client-ID", self.client_id.clone())
+                        .header("Authorization", format!("Bearer {}", token.access_token))
+                        .headers(headers)
+                        .send()
+                        .await
+                        .and_then(|response| response.error_for_status());
+                }
+            }
+        }
+
+        let json = match res {
+            Ok(response) => match response.json::<serde_json::Value>().await {
+                Ok(json) => json,
+                Err(error) => {
+                    println!("Error parsing IGDB search_game response: {}", error);
+                    return vec![];
+                }
+            },
+            Err(error) => {
+                println!("Error calling IGDB search_game: {}", error);
+                return vec![];
+            }
+        };
 
         // convert the json response to a vector of game structs
-        let games: Vec<ScrapeEntry> = res
-            .unwrap()
+        json
             .as_array()
-            .unwrap()
-            .into_iter()
-            .map(|game| ScrapeEntry {
-                id: game["id"].as_u64().unwrap(),
-                slug: game["slug"].as_str().unwrap().to_string(),
-                name: game["name"].as_str().unwrap().to_string(),
-                gamedb: "igdb".to_string(),
-                file: "unknown".to_string(),
-                last_scraped: "".to_string(),
-                json: serde_json::Value::Null,
+            .map(|games| {
+                games
+                    .into_iter()
+                    .filter_map(|game| {
+                        Some(ScrapeEntry {
+                            id: game["id"].as_u64()?,
+                            slug: game["slug"].as_str()?.to_string(),
+                            name: game["name"].as_str()?.to_string(),
+                            gamedb: "igdb".to_string(),
+                            file: "unknown".to_string(),
+                            last_scraped: "".to_string(),
+                            json: serde_json::Value::Null,
+                        })
+                    })
+                    .collect()
             })
-            .collect();
-
-        games
+            .unwrap_or_default()
     }
 
     async fn search_platform(&mut self, _name: &str) -> Vec<PlatformEntry> {
-        
+
         let token = self.authentiate().await;
 
         if token.is_err() {
             println!(
                 "Error authenticating with IGDB. You may need to check your configured credentials."
             );
+            return vec![];
         }
 
         let token = token.unwrap();
@@ -115,70 +174,112 @@ impl GameDatabase for IgdbDatabase {
 
         let url = format!("https://api.igdb.com/v4/platforms");
 
-        let client = reqwest::blocking::Client::new();
+        let client = reqwest::Client::new();
         let mut headers = HeaderMap::new();
         headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
         headers.insert(USER_AGENT, HeaderValue::from_static("reqwest"));
-    
-        let res = client
+
+        const BODY: &str = "fields abbreviation,alternative_name,category,checksum,created_at,generation,name,platform_family,platform_logo,slug,summary,updated_at,url,versions,websites;";
+
+        let mut res = client
             .post(&url)
-            .body("fields abbreviation,alternative_name,category,checksum,created_at,generation,name,platform_family,platform_logo,slug,summary,updated_at,url,versions,websites;")
-            .header("Client-ID", "lpzomulxapy5mrfftuxcnwidw5ob2q")
+            .body(BODY)
+            .header("Client-ID", self.client_id.clone())
             .header("Authorization", format!("Bearer {}", token.access_token))
-            .headers(headers)
+            .headers(headers.clone())
             .send()
-            .unwrap()
-            .json::<serde_json::Value>();
+            .await
+            .and_then(|response| response.error_for_status());
+
+        if let Err(error) = &res {
+            if error.status() == Some(reqwest::StatusCode::UNAUTHORIZED) {
+                println!("IGDB rejected our cached token as expired; re-authenticating once");
+                crate::scrapers::scrapers::invalidate_cached_token("igdb");
+                if let Ok(token) = self.authentiate().await {
+                    self.auth_token = token.clone();
+                    res = client
+                        .post(&url)
+                        .body(BODY)
+                        .header("Client-ID", self.client_id.clone())
+                        .header("Authorization", format!("Bearer {}", token.access_token))
+                        .headers(headers)
+                        .send()
+                        .await
+                        .and_then(|response| response.error_for_status());
+                }
+            }
+        }
+
+        let json = match res {
+            Ok(response) => match response.json::<serde_json::Value>().await {
+                Ok(json) => json,
+                Err(error) => {
+                    println!("Error parsing IGDB search_platform response: {}", error);
+                    return vec![];
+                }
+            },
+            Err(error) => {
+                println!("Error calling IGDB search_platform: {}", error);
+                return vec![];
+            }
+        };
 
         // convert the json response to a vector of platform structs
-        let platforms: Vec<PlatformEntry> = res
-            .unwrap()
+        json
             .as_array()
-            .unwrap()
-            .into_iter()
-            .map(|game| PlatformEntry {
-                id: game["id"].as_u64().unwrap(),
-                slug: game["slug"].as_str().unwrap().to_string(),
-                name: game["name"].as_str().unwrap().to_string(),
-                file: "unknown".to_string(),
-                gamedb: "igdb".to_string(),
-                last_scraped: "".to_string(),
-                json: serde_json::Value::Null,
+            .map(|platforms| {
+                platforms
+                    .into_iter()
+                    .filter_map(|game| {
+                        Some(PlatformEntry {
+                            id: game["id"].as_u64()?,
+                            slug: game["slug"].as_str()?.to_string(),
+                            name: game["name"].as_str()?.to_string(),
+                            file: "unknown".to_string(),
+                            gamedb: "igdb".to_string(),
+                            last_scraped: "".to_string(),
+                            json: serde_json::Value::Null,
+                        })
+                    })
+                    .collect()
             })
-            .collect();
-
-        platforms
+            .unwrap_or_default()
     }
 
-    fn get_game_data(&mut self, id: u64, name: String) -> ScrapeEntry {
-        
+    async fn get_game_data(&mut self, id: u64, name: String) -> ScrapeEntry {
+
         println!("Getting game data for id: {}", id);
 
         let url = format!("https://api.igdb.com/v4/games");
 
-        let client = reqwest::blocking::Client::new();
+        let client = reqwest::Client::new();
         let mut headers = HeaderMap::new();
         headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
         headers.insert(USER_AGENT, HeaderValue::from_static("reqwest"));
 
         let res = client
             .post(&url)
-            .header("Client-ID", "lpzomulxapy5mrfftuxcnwidw5ob2q")
+            .header("Client-ID", self.client_id.clone())
             .header("Authorization", format!("Bearer {}", self.auth_token.access_token))
             .headers(headers)
             .body(format!("fields *, screenshots.*; where id = {};", id))
             .send()
-            .unwrap()
-            .json::<serde_json::Value>();
+            .await
+            .and_then(|response| response.error_for_status());
+
+        let res = match res {
+            Ok(response) => response.json::<serde_json::Value>().await,
+            Err(error) => Err(error),
+        };
 
         // check if res is ok and convert res to a json object, handle the error if not ok
-        let game_data_values = match res {
+        match res {
             Ok(json) => {
                 // create a game object from the json response
                 ScrapeEntry {
                     id: id,
-                    name: json[0]["name"].as_str().unwrap().to_string(),
-                    slug: json[0]["slug"].as_str().unwrap().to_string(),
+                    name: json[0]["name"].as_str().unwrap_or(&name).to_string(),
+                    slug: json[0]["slug"].as_str().unwrap_or("unknown").to_string(),
                     file: "unknown".to_string(),
                     gamedb: "igdb".to_string(),
                     last_scraped: "".to_string(),
@@ -197,43 +298,46 @@ impl GameDatabase for IgdbDatabase {
                     json: serde_json::Value::Null,
                 }
             }
-        };
-
-        game_data_values
+        }
     }
 
-    fn get_platform_data(&mut self, id: u64, name: String) -> PlatformEntry {
-        
+    async fn get_platform_data(&mut self, id: u64, name: String) -> PlatformEntry {
+
         println!("Getting platform data for id: {}", id);
 
         let url = format!("https://api.igdb.com/v4/platforms");
 
-        let client = reqwest::blocking::Client::new();
+        let client = reqwest::Client::new();
         let mut headers = HeaderMap::new();
         headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
         headers.insert(USER_AGENT, HeaderValue::from_static("reqwest"));
 
         let res = client
             .post(&url)
-            .header("Client-ID", "lpzomulxapy5mrfftuxcnwidw5ob2q")
+            .header("Client-ID", self.client_id.clone())
             .header("Authorization", format!("Bearer {}", self.auth_token.access_token))
             .headers(headers)
             .body(format!("fields *; where id = {};", id))
             .send()
-            .unwrap()
-            .json::<serde_json::Value>();
+            .await
+            .and_then(|response| response.error_for_status());
+
+        let res = match res {
+            Ok(response) => response.json::<serde_json::Value>().await,
+            Err(error) => Err(error),
+        };
 
         // print the res
         println!("{:?}", res);
 
         // check if res is ok and convert res to a json object, handle the error if not ok
-        let platform_data_values = match res {
+        match res {
             Ok(json) => {
                 // create a game object from the json response
                 PlatformEntry {
                     id: id,
-                    name: json[0]["name"].as_str().unwrap().to_string(),
-                    slug: json[0]["slug"].as_str().unwrap().to_string(),
+                    name: json[0]["name"].as_str().unwrap_or(&name).to_string(),
+                    slug: json[0]["slug"].as_str().unwrap_or("unknown").to_string(),
                     gamedb: "igdb".to_string(),
                     file: "unknown".to_string(),
                     last_scraped: "".to_string(),
@@ -252,32 +356,96 @@ impl GameDatabase for IgdbDatabase {
                     json: serde_json::Value::Null,
                 }
             }
-        };
-
-        platform_data_values
+        }
     }
 
-    fn get_screenshots(&mut self, id: u64, screenshot_info: Vec<ScreenshotInfo>) -> u32 {
-        
-        println!("Getting screenshots for id: {}", id);
+    // Enqueues each screenshot through the resumable file-transfer subsystem
+    // instead of a one-shot blocking GET, so artwork fetches get the same
+    // progress reporting and resume behavior as any other download.
+    async fn get_screenshots(&mut self, scrape_entry: &ScrapeEntry, screenshot_info: Vec<ScreenshotInfo>) -> u32 {
 
-        // Iterate through the screenshot info and get the screenshots
-        let mut num_screenshots: u32 = 0;
+        println!("Getting screenshots for id: {}", scrape_entry.id);
+
+        let correlation_id = scrape_entry.get_identified_slug();
+
+        let mut transfers = Vec::new();
         for screenshot in screenshot_info {
-            println!("{:?}", screenshot.file_path);
             // First, check to see if the screenshot is already downloaded
             if std::path::Path::new(&screenshot.file_path).exists() {
                 println!("Screenshot ID {} already downloaded. Skipping...", screenshot.id);
                 continue;
             }
-            let mut file = std::fs::File::create(screenshot.file_path).unwrap();
-            let mut response = reqwest::blocking::get(&screenshot.url).unwrap();
-            response.copy_to(&mut file).unwrap();
-            drop(file);
-            num_screenshots+=1;
+            let Some(transfer_id) =
+                network_manager::add_file_transfer(screenshot.url.clone(), screenshot.file_path.clone()).await
+            else {
+                println!("Server is shutting down - skipping screenshot ID {}", screenshot.id);
+                continue;
+            };
+            network_manager::set_correlation_id(transfer_id, correlation_id.clone()).await;
+            transfers.push((transfer_id, screenshot));
+        }
+
+        let mut num_screenshots: u32 = 0;
+        for (transfer_id, screenshot) in transfers {
+            network_manager::start_file_transfer(transfer_id, screenshot.url.clone()).await;
+
+            let completed = {
+                let ft_map = network_manager::acquire_file_transfers_map().await;
+                ft_map
+                    .get(&transfer_id)
+                    .map(|ft| ft.status.state == FileTransferState::Completed)
+                    .unwrap_or(false)
+            };
+            if completed {
+                num_screenshots += 1;
+                generate_thumbnails(&screenshot).await;
+            }
+            network_manager::remove_file_transfer(transfer_id).await;
         }
 
         num_screenshots
     }
-    
+
+}
+
+/// Decodes `screenshot`'s freshly-downloaded original and writes bounded-size
+/// thumbnail variants at each of `THUMBNAIL_SIZES` (long edge, aspect
+/// preserved), skipping any that already exist - mirrors the existence check
+/// `get_screenshots` uses for the original itself. Runs on a blocking thread
+/// since image decode/resize is CPU-bound.
+///
+/// This `IgdbDatabase` is the legacy, file-path-based scraper - its
+/// screenshots live directly on disk rather than in the content-addressed
+/// `MediaStore`, so its thumbnails do too. `gamedb::igdb::IgdbDatabase`'s
+/// `screenshot_queue` is the actively-developed pipeline and has its own
+/// `MediaStore`-backed equivalent (`screenshot_queue::generate_thumbnails`).
+async fn generate_thumbnails(screenshot: &ScreenshotInfo) {
+    let missing: Vec<(u32, std::path::PathBuf)> = THUMBNAIL_SIZES
+        .iter()
+        .map(|size| (*size, screenshot.thumbnail_path(*size)))
+        .filter(|(_, path)| !path.exists())
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+
+    let file_path = screenshot.file_path.clone();
+    let result = tokio::task::spawn_blocking(move || -> Result<(), image::ImageError> {
+        let original = image::open(&file_path)?;
+        for (size, thumbnail_path) in &missing {
+            original.thumbnail(*size, *size).save(thumbnail_path)?;
+        }
+        Ok(())
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => {
+            println!("Could not generate thumbnails for {:?}: {}", screenshot.file_path, error)
+        }
+        Err(error) => {
+            println!("Thumbnail generation panicked for {:?}: {}", screenshot.file_path, error)
+        }
+    }
 }
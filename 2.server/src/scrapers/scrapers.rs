@@ -1,8 +1,12 @@
 
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use crate::handlers::files;
 use crate::scrapers::igdb::IgdbDatabase;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -67,22 +71,84 @@ impl PlatformEntry {
 
 }
 
+/// Long-edge pixel sizes thumbnails are generated at alongside each
+/// downloaded screenshot - see `IgdbDatabase::get_screenshots`.
+pub const THUMBNAIL_SIZES: [u32; 2] = [320, 640];
+
+impl ScreenshotInfo {
+    /// Where a `max_dimension` thumbnail of this screenshot is (or will be)
+    /// written, derived from `file_path` - e.g. `foo.jpg` at 320px becomes
+    /// `foo_320.jpg`, sitting next to the full-resolution original.
+    pub fn thumbnail_path(&self, max_dimension: u32) -> PathBuf {
+        let stem = self.file_path.file_stem().and_then(|s| s.to_str()).unwrap_or("screenshot");
+        let ext = self.file_path.extension().and_then(|s| s.to_str()).unwrap_or("jpg");
+        self.file_path.with_file_name(format!("{}_{}.{}", stem, max_dimension, ext))
+    }
+}
+
+
+/// Twitch/IGDB app credentials for `IgdbDatabase` - read from
+/// `dillinger_config.toml`'s `[gamedb.igdb]` section first, then the
+/// `IGDB_CLIENT_ID`/`IGDB_CLIENT_SECRET` env vars - see
+/// `IgdbCredentials::resolve`. There's no hardcoded fallback, so a
+/// deployment has to supply its own Twitch app instead of inheriting
+/// whatever was baked into source.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IgdbCredentials {
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+}
+
+impl IgdbCredentials {
+    /// Resolves both credentials, checking config then the environment, and
+    /// erroring out if neither source has them.
+    pub fn resolve(&self) -> Result<(String, String), String> {
+        let client_id = self
+            .client_id
+            .clone()
+            .or_else(|| std::env::var("IGDB_CLIENT_ID").ok())
+            .ok_or_else(|| "No IGDB client_id configured - set [gamedb.igdb].client_id or IGDB_CLIENT_ID".to_string())?;
+        let client_secret = self
+            .client_secret
+            .clone()
+            .or_else(|| std::env::var("IGDB_CLIENT_SECRET").ok())
+            .ok_or_else(|| {
+                "No IGDB client_secret configured - set [gamedb.igdb].client_secret or IGDB_CLIENT_SECRET".to_string()
+            })?;
+        Ok((client_id, client_secret))
+    }
+}
+
+/// Top-level `[gamedb]` config section for the legacy `scrapers::igdb`
+/// client - see `gamedb::registry::GameDbProviderConfig` for the newer,
+/// multi-provider `GameDb` stack's equivalent.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GamedbSection {
+    #[serde(default)]
+    pub igdb: IgdbCredentials,
+}
 
 pub struct AuthToken {
     pub db: String,
     pub access_token: String,
     pub expires_in: u64,
     pub token_type: String,
+    // When this token was issued, so its age can be checked against
+    // `expires_in` without the server ever telling us an absolute expiry time.
+    pub(crate) issued_at: Instant,
 }
 
 impl AuthToken {
-    
+
     pub(crate) fn clone(&self) -> AuthToken {
         AuthToken {
             db: self.db.clone(),
             access_token: self.access_token.clone(),
             expires_in: self.expires_in,
             token_type: self.token_type.clone(),
+            issued_at: self.issued_at,
         }
     }
 
@@ -92,8 +158,117 @@ impl AuthToken {
             access_token: "".to_string(),
             expires_in: 0,
             token_type: "".to_string(),
+            issued_at: Instant::now(),
         }
     }
+
+    /// Whether this token is still good to use, allowing `SAFETY_MARGIN_SECS`
+    /// of headroom so a scrape in progress doesn't get a 401 right as the
+    /// clock runs out.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.issued_at.elapsed().as_secs() + TOKEN_EXPIRY_SAFETY_MARGIN_SECS < self.expires_in
+    }
+}
+
+/// How much earlier than its advertised `expires_in` a token is treated as
+/// expired.
+const TOKEN_EXPIRY_SAFETY_MARGIN_SECS: u64 = 60;
+
+lazy_static! {
+    // Caches the last valid token issued per game db name, so repeated
+    // scrapes in the same process don't re-authenticate on every call.
+    static ref TOKEN_CACHE: Mutex<HashMap<String, AuthToken>> = Mutex::new(HashMap::new());
+}
+
+/// On-disk representation of an `AuthToken` - `Instant` can't be serialized
+/// since it's only meaningful relative to the current process, so the
+/// acquisition time is stored as Unix seconds instead and converted back to
+/// an `Instant` on load.
+#[derive(Serialize, Deserialize)]
+struct PersistedToken {
+    db: String,
+    access_token: String,
+    expires_in: u64,
+    token_type: String,
+    issued_at_epoch_secs: u64,
+}
+
+fn token_cache_path(db: &str) -> PathBuf {
+    files::get_cache_dir().join("tokens").join(format!("{}.json", db))
+}
+
+/// Returns a still-valid cached token for `db` - checking the in-memory
+/// cache first, then falling back to the on-disk copy (see `cache_token`) so
+/// a restarted process doesn't have to re-authenticate right away.
+pub(crate) fn cached_token(db: &str) -> Option<AuthToken> {
+    {
+        let cache = TOKEN_CACHE.lock().unwrap();
+        if let Some(token) = cache.get(db).filter(|token| token.is_valid()) {
+            return Some(token.clone());
+        }
+    }
+
+    let persisted = load_persisted_token(db)?;
+    if !persisted.is_valid() {
+        return None;
+    }
+    let mut cache = TOKEN_CACHE.lock().unwrap();
+    cache.insert(db.to_string(), persisted.clone());
+    Some(persisted)
+}
+
+/// Replaces the cached token for `token.db`, both in memory and on disk.
+pub(crate) fn cache_token(token: AuthToken) {
+    persist_token(&token);
+    let mut cache = TOKEN_CACHE.lock().unwrap();
+    cache.insert(token.db.clone(), token);
+}
+
+/// Discards the cached token for `db`, e.g. after the remote API rejects it
+/// as expired (a 401) despite still being inside our locally cached expiry
+/// window.
+pub(crate) fn invalidate_cached_token(db: &str) {
+    let mut cache = TOKEN_CACHE.lock().unwrap();
+    cache.remove(db);
+    let _ = std::fs::remove_file(token_cache_path(db));
+}
+
+fn persist_token(token: &AuthToken) {
+    let issued_at_epoch_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .saturating_sub(token.issued_at.elapsed())
+        .as_secs();
+
+    let persisted = PersistedToken {
+        db: token.db.clone(),
+        access_token: token.access_token.clone(),
+        expires_in: token.expires_in,
+        token_type: token.token_type.clone(),
+        issued_at_epoch_secs,
+    };
+    match serde_json::to_string(&persisted) {
+        Ok(json) => files::write_file(&token_cache_path(&token.db), json, true),
+        Err(e) => println!("Could not serialize token for {}: {}", token.db, e),
+    }
+}
+
+fn load_persisted_token(db: &str) -> Option<AuthToken> {
+    let content = files::read_file(&token_cache_path(db))?;
+    let persisted: PersistedToken = serde_json::from_str(&content).ok()?;
+
+    let now_epoch_secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let elapsed_secs = now_epoch_secs.saturating_sub(persisted.issued_at_epoch_secs);
+
+    Some(AuthToken {
+        db: persisted.db,
+        access_token: persisted.access_token,
+        expires_in: persisted.expires_in,
+        token_type: persisted.token_type,
+        issued_at: Instant::now()
+            .checked_sub(std::time::Duration::from_secs(elapsed_secs))
+            .unwrap_or_else(Instant::now),
+    })
 }
 
 #[async_trait]
@@ -101,9 +276,9 @@ pub trait GameDatabase : Send  {
     async fn authentiate(&mut self) -> Result<AuthToken, reqwest::Error>;
     async fn search_game(&mut self, name: &str) -> Vec<ScrapeEntry>;
     async fn search_platform(&mut self, name: &str) -> Vec<PlatformEntry>;
-    fn get_game_data(&mut self, id: u64, name: String) -> ScrapeEntry;
-    fn get_platform_data(&mut self, id: u64, name: String) -> PlatformEntry;
-    fn get_screenshots(&mut self, id: u64, screenshot_info: Vec<ScreenshotInfo>) -> u32;
+    async fn get_game_data(&mut self, id: u64, name: String) -> ScrapeEntry;
+    async fn get_platform_data(&mut self, id: u64, name: String) -> PlatformEntry;
+    async fn get_screenshots(&mut self, scrape_entry: &ScrapeEntry, screenshot_info: Vec<ScreenshotInfo>) -> u32;
 }
 
 
@@ -117,7 +292,15 @@ impl Scraper {
         let mut gamedb: Option<Box<dyn GameDatabase>> = None;
         match game_db.as_str() {
             "igdb" => {
-                gamedb = Some(Box::new(IgdbDatabase { auth_token: AuthToken::new() }));
+                let config = crate::config::get_master_config();
+                match config.gamedb.igdb.resolve() {
+                    Ok((client_id, client_secret)) => {
+                        gamedb = Some(Box::new(IgdbDatabase::new(client_id, client_secret)));
+                    }
+                    Err(e) => {
+                        println!("Could not build the IGDB scraper: {}", e);
+                    }
+                }
             }
             _ => { println!("Unknown game db {:?}. Scrape cancelled.", game_db); drop(None::<ScrapeEntry>);}
         }
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use log::info;
+use tokio::fs;
+use tokio::signal::unix::{signal, SignalKind};
+
+use crate::core::DillingerCore;
+use crate::handlers::socket_client;
+use crate::network::network_manager;
+
+/// Resolves once either SIGINT or SIGTERM is received, for use as the signal
+/// future in `warp::serve(...).bind_with_graceful_shutdown(addr, signal)`.
+pub async fn wait_for_shutdown_signal() {
+    let mut sigterm =
+        signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => info!("Received SIGINT, starting graceful shutdown"),
+        _ = sigterm.recv() => info!("Received SIGTERM, starting graceful shutdown"),
+    }
+
+    network_manager::begin_shutdown();
+}
+
+/// Runs once the server has stopped accepting new connections: checkpoints
+/// in-flight transfers, flushes the game cache to disk atomically, and
+/// tells connected websocket clients the server is going away.
+pub async fn run_shutdown_tasks(core: Arc<DillingerCore>) {
+    info!("Running shutdown tasks");
+
+    network_manager::checkpoint_all_transfers().await;
+    flush_game_cache(&core).await;
+
+    socket_client::send_message(
+        serde_json::json!({ "component": "server", "event": "shutting_down" }).to_string(),
+    )
+    .await;
+
+    info!("Shutdown tasks complete");
+}
+
+// Writes the current game cache to `game_cache.toml` via write-temp-then-rename,
+// so a crash mid-write can never leave a half-written cache file behind.
+async fn flush_game_cache(core: &Arc<DillingerCore>) {
+    let cache = core.game_cache.read().await;
+    let toml = match toml::to_string(&*cache) {
+        Ok(toml) => toml,
+        Err(e) => {
+            log::warn!("Could not serialize game cache on shutdown: {:?}", e);
+            return;
+        }
+    };
+
+    let final_path = core.config.root_dir.join("game_cache.toml");
+    let temp_path = core.config.root_dir.join("game_cache.toml.tmp");
+
+    if let Err(e) = fs::write(&temp_path, toml).await {
+        log::warn!("Could not write temporary game cache file: {:?}", e);
+        return;
+    }
+    if let Err(e) = fs::rename(&temp_path, &final_path).await {
+        log::warn!("Could not rename temporary game cache file into place: {:?}", e);
+    }
+}
@@ -0,0 +1,298 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use crate::config::MasterConfig;
+
+/// Which backend `storage::open` hands back, configurable via
+/// `MasterConfig::store_backend` - mirrors
+/// `network::transfer_repo::TransferRepoBackend`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum StoreBackend {
+    File,
+    Object,
+}
+
+impl Default for StoreBackend {
+    fn default() -> Self {
+        StoreBackend::File
+    }
+}
+
+/// Connection details for `StoreBackend::Object` - only read when that
+/// backend is selected.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ObjectStoreConfig {
+    pub bucket: String,
+    pub region: String,
+    /// Set for S3-compatible services (MinIO, garage, ...); left unset to
+    /// talk to AWS S3 directly.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Key prefix under which everything this store writes is namespaced.
+    #[serde(default)]
+    pub prefix: String,
+}
+
+/// Abstracts away where downloaded files and scraped assets live, so a
+/// headless instance can target network/object storage instead of the local
+/// disk `start_file_transfer`, `cache::write_cache_last_search` and the
+/// scraper's screenshot saving used to assume directly - mirrors pict-rs's
+/// `FileStore`/`ObjectStore` split.
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Current length of `key` in bytes, or `None` if it doesn't exist yet.
+    async fn len(&self, key: &str) -> Option<u64>;
+
+    /// Ensures `key` exists and is exactly `total_len` bytes, so positioned
+    /// writes can land anywhere in it regardless of what order they arrive.
+    async fn allocate(&self, key: &str, total_len: u64) -> Result<(), String>;
+
+    /// Writes `data` at `offset` bytes into `key`.
+    async fn write_at(&self, key: &str, offset: u64, data: &[u8]) -> Result<(), String>;
+
+    /// Appends `data` to the end of `key`, creating it if it doesn't exist.
+    async fn append(&self, key: &str, data: &[u8]) -> Result<(), String>;
+
+    /// Truncates `key` to zero length, creating it if it doesn't exist - used
+    /// before a non-resumable download starts over.
+    async fn truncate(&self, key: &str) -> Result<(), String>;
+
+    /// Reads the whole of `key` back, e.g. for checksum verification.
+    async fn read_all(&self, key: &str) -> Result<Vec<u8>, String>;
+
+    /// Replaces `key`'s entire contents with `data` in one shot.
+    async fn write(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        self.truncate(key).await?;
+        self.append(key, data).await
+    }
+}
+
+/// The original local-disk behavior. `key` is joined onto `root` - if `key`
+/// happens to be an absolute path (as file transfer destinations are today),
+/// `PathBuf::join` discards `root` and uses it as-is, so the same `FileStore`
+/// serves both root-relative keys (the search cache, screenshots) and
+/// absolute ones (file transfer destinations) without special-casing.
+pub struct FileStore {
+    root: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(root: PathBuf) -> Self {
+        FileStore { root }
+    }
+
+    fn resolve(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[async_trait]
+impl Store for FileStore {
+    async fn len(&self, key: &str) -> Option<u64> {
+        fs::metadata(self.resolve(key)).await.ok().map(|m| m.len())
+    }
+
+    async fn allocate(&self, key: &str, total_len: u64) -> Result<(), String> {
+        let path = self.resolve(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Could not create {:?}: {:?}", parent, e))?;
+        }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(&path)
+            .await
+            .map_err(|e| format!("Could not create {:?}: {:?}", path, e))?;
+        file.set_len(total_len)
+            .await
+            .map_err(|e| format!("Could not pre-allocate {:?}: {:?}", path, e))
+    }
+
+    async fn write_at(&self, key: &str, offset: u64, data: &[u8]) -> Result<(), String> {
+        let path = self.resolve(key);
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .await
+            .map_err(|e| format!("Could not open {:?}: {:?}", path, e))?;
+        file.seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(|e| format!("Seek failed on {:?}: {:?}", path, e))?;
+        file.write_all(data)
+            .await
+            .map_err(|e| format!("Write failed on {:?}: {:?}", path, e))
+    }
+
+    async fn append(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        let path = self.resolve(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Could not create {:?}: {:?}", parent, e))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(|e| format!("Could not open {:?}: {:?}", path, e))?;
+        file.write_all(data)
+            .await
+            .map_err(|e| format!("Write failed on {:?}: {:?}", path, e))
+    }
+
+    async fn truncate(&self, key: &str) -> Result<(), String> {
+        let path = self.resolve(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Could not create {:?}: {:?}", parent, e))?;
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Could not truncate {:?}: {:?}", path, e))
+    }
+
+    async fn read_all(&self, key: &str) -> Result<Vec<u8>, String> {
+        let path = self.resolve(key);
+        fs::read(&path).await.map_err(|e| format!("Could not read {:?}: {:?}", path, e))
+    }
+}
+
+/// An S3-compatible backend (AWS, MinIO, garage, ...) so a headless instance
+/// can keep its library off local disk entirely. Positioned writes are
+/// implemented as a read-modify-write of the whole object, since object
+/// storage has no equivalent of a local seek+write - fine for the
+/// metadata/screenshot-sized objects this is mainly meant for; the parallel
+/// multi-segment downloader still requires `StoreBackend::File`.
+pub struct ObjectStore {
+    bucket: s3::Bucket,
+    prefix: String,
+}
+
+impl ObjectStore {
+    pub fn new(config: &ObjectStoreConfig) -> Result<Self, String> {
+        let region = match &config.endpoint {
+            Some(endpoint) => s3::Region::Custom {
+                region: config.region.clone(),
+                endpoint: endpoint.clone(),
+            },
+            None => config
+                .region
+                .parse()
+                .map_err(|e| format!("Invalid region {}: {:?}", config.region, e))?,
+        };
+        let credentials = s3::creds::Credentials::new(
+            Some(&config.access_key_id),
+            Some(&config.secret_access_key),
+            None,
+            None,
+            None,
+        )
+        .map_err(|e| format!("Invalid object store credentials: {:?}", e))?;
+        let bucket = s3::Bucket::new(&config.bucket, region, credentials)
+            .map_err(|e| format!("Could not configure bucket {}: {:?}", config.bucket, e))?
+            .with_path_style();
+        Ok(ObjectStore {
+            bucket,
+            prefix: config.prefix.clone(),
+        })
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix.trim_end_matches('/'), key)
+        }
+    }
+}
+
+#[async_trait]
+impl Store for ObjectStore {
+    async fn len(&self, key: &str) -> Option<u64> {
+        let (head, code) = self.bucket.head_object(self.object_key(key)).await.ok()?;
+        if code == 200 {
+            head.content_length.map(|len| len as u64)
+        } else {
+            None
+        }
+    }
+
+    async fn allocate(&self, _key: &str, _total_len: u64) -> Result<(), String> {
+        // No pre-allocation primitive for object storage - writes create the
+        // object on first use instead.
+        Ok(())
+    }
+
+    async fn write_at(&self, key: &str, offset: u64, data: &[u8]) -> Result<(), String> {
+        let mut buf = self.read_all(key).await.unwrap_or_default();
+        let end = offset as usize + data.len();
+        if buf.len() < end {
+            buf.resize(end, 0);
+        }
+        buf[offset as usize..end].copy_from_slice(data);
+        self.bucket
+            .put_object(self.object_key(key), &buf)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Could not write object {}: {:?}", key, e))
+    }
+
+    async fn append(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        let mut buf = self.read_all(key).await.unwrap_or_default();
+        buf.extend_from_slice(data);
+        self.bucket
+            .put_object(self.object_key(key), &buf)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Could not write object {}: {:?}", key, e))
+    }
+
+    async fn truncate(&self, key: &str) -> Result<(), String> {
+        self.bucket
+            .put_object(self.object_key(key), &[])
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Could not truncate object {}: {:?}", key, e))
+    }
+
+    async fn read_all(&self, key: &str) -> Result<Vec<u8>, String> {
+        let response = self
+            .bucket
+            .get_object(self.object_key(key))
+            .await
+            .map_err(|e| format!("Could not read object {}: {:?}", key, e))?;
+        Ok(response.bytes().to_vec())
+    }
+}
+
+/// Opens the backend selected by `MasterConfig::store_backend`.
+pub fn open(config: &MasterConfig) -> Arc<dyn Store> {
+    match config.store_backend {
+        StoreBackend::File => Arc::new(FileStore::new(config.root_dir.clone())),
+        StoreBackend::Object => {
+            let object_config = config
+                .object_store
+                .as_ref()
+                .unwrap_or_else(|| panic!("store_backend = Object requires an [object_store] section"));
+            Arc::new(
+                ObjectStore::new(object_config)
+                    .unwrap_or_else(|e| panic!("Could not open object store: {}", e)),
+            )
+        }
+    }
+}
@@ -43,6 +43,6 @@ async fn basic() {
 async fn deserialize_config() {
     init_logger();
     info!("Running config deserializer");
-    let config = GLOBAL_CONFIG.root_dir.clone();
+    let config = GLOBAL_CONFIG.load().root_dir.clone();
     assert!(true);
 }
@@ -0,0 +1,101 @@
+// End-to-end harness: brings up the real server (plus a throwaway docker
+// daemon) via `docker-compose.test.yml` and drives it over HTTP/websocket,
+// rather than calling handler functions directly in-process like the other
+// tests in this module do.
+use log::info;
+use std::process::Command;
+use std::sync::Once;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const COMPOSE_FILE: &str = "docker-compose.test.yml";
+const BASE_URL: &str = "http://localhost:8088";
+
+static INIT: Once = Once::new();
+
+fn init_logger() {
+    INIT.call_once(|| {
+        env_logger::init();
+    });
+}
+
+/// Brings the compose stack up on construction and tears it down on drop, so
+/// a failing assertion still leaves the containers cleaned up.
+struct ComposeStack;
+
+impl ComposeStack {
+    fn up() -> Self {
+        let status = Command::new("docker")
+            .args(["compose", "-f", COMPOSE_FILE, "up", "--build", "-d"])
+            .status()
+            .expect("failed to run `docker compose up` - is docker installed?");
+        assert!(status.success(), "docker compose up failed");
+        ComposeStack
+    }
+}
+
+impl Drop for ComposeStack {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["compose", "-f", COMPOSE_FILE, "down", "-v"])
+            .status();
+    }
+}
+
+// Polls `diag/ping` until it answers "pong" or we give up.
+async fn wait_for_server(client: &reqwest::Client) {
+    for attempt in 0..30 {
+        if let Ok(response) = client.get(format!("{}/diag/ping", BASE_URL)).send().await {
+            if let Ok(body) = response.text().await {
+                if body.contains("pong") {
+                    info!("Server answered ping after {} attempt(s)", attempt + 1);
+                    return;
+                }
+            }
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+    panic!("Server never answered diag/ping");
+}
+
+#[tokio::test]
+#[ignore] // Requires docker compose and is slow - run explicitly in CI.
+async fn test_full_stack_roundtrip() {
+    init_logger();
+    let _stack = ComposeStack::up();
+
+    let client = reqwest::Client::new();
+    wait_for_server(&client).await;
+
+    // Build the cache from the seeded fixtures, then give it a moment to run.
+    let build_response = client
+        .get(format!("{}/mgmt/build_game_cache", BASE_URL))
+        .send()
+        .await
+        .expect("build_game_cache request failed");
+    assert!(build_response.status().is_success());
+    sleep(Duration::from_secs(2)).await;
+
+    // The fixtures seed a "quake" game - confirm it shows up in local search.
+    let search_response = client
+        .get(format!("{}/search/local/quake", BASE_URL))
+        .send()
+        .await
+        .expect("search/local request failed");
+    assert!(search_response.status().is_success());
+    let results: serde_json::Value = search_response.json().await.unwrap();
+    assert!(results.as_array().map(|a| !a.is_empty()).unwrap_or(false));
+
+    // Running containers should at least respond, even with none running.
+    let containers_response = client
+        .get(format!("{}/sys/list_containers", BASE_URL))
+        .send()
+        .await
+        .expect("sys/list_containers request failed");
+    assert!(containers_response.status().is_success());
+
+    // Finally, confirm the websocket route upgrades cleanly.
+    let ws_url = format!("ws://localhost:8088/ws");
+    let ws_result = tokio_tungstenite::connect_async(ws_url).await;
+    assert!(ws_result.is_ok(), "websocket upgrade failed");
+}
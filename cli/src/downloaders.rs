@@ -0,0 +1,137 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+use dillinger_lib::status::StatusObj;
+
+/// Streams `url` to `dest`, sending a `StatusObj` over `tx` for every chunk
+/// read - bytes-downloaded over `Content-Length` becomes `progress` - so a
+/// progress bar or a scrolling log can follow along without the download
+/// core knowing anything about either frontend.
+pub fn download_with_progress(url: &str, dest: &Path, tx: &Sender<StatusObj>) -> Result<(), String> {
+    let label = dest
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| url.to_string());
+
+    let mut response = reqwest::blocking::get(url).map_err(|e| e.to_string())?;
+    let content_length = response.content_length();
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut file = File::create(dest).map_err(|e| e.to_string())?;
+
+    let mut downloaded: u64 = 0;
+    let mut buf = [0u8; 8192];
+
+    loop {
+        let read = response.read(&mut buf).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buf[..read]).map_err(|e| e.to_string())?;
+        downloaded += read as u64;
+
+        let _ = tx.send(StatusObj {
+            label: Some(label.clone()),
+            progress: content_length.map(|total| downloaded as f64 / total as f64),
+            complete: false,
+            log_line: Some(format!("{} - {} bytes", label, downloaded)),
+            error: None,
+        });
+    }
+
+    let _ = tx.send(StatusObj {
+        label: Some(label.clone()),
+        progress: Some(1.0),
+        complete: true,
+        log_line: Some(format!("{} - done", label)),
+        error: None,
+    });
+
+    Ok(())
+}
+
+/// Computes the SHA-256 digest of an on-disk file, hex-encoded.
+pub fn sha256_digest(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Computes the MD5 digest of an on-disk file, hex-encoded - GOG-style
+/// checksums are published as MD5, not SHA-256.
+pub fn md5_digest(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let mut hasher = Md5::new();
+    hasher.update(&bytes);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Re-hashes `path` and compares it against `expected`, picking MD5 or
+/// SHA-256 by the length of the expected digest (32 hex chars vs 64).
+pub fn verify_digest(path: &Path, expected: &str) -> Result<bool, String> {
+    let actual = if expected.len() == 32 {
+        md5_digest(path)?
+    } else {
+        sha256_digest(path)?
+    };
+    Ok(actual.eq_ignore_ascii_case(expected))
+}
+
+/// Like `download_with_progress`, but afterwards computes a digest of the
+/// downloaded file and, if `expected_hash` is `Some`, verifies it inline -
+/// failing fast on a corrupt/partial download instead of leaving it to be
+/// discovered later by `games verify`. Returns the computed digest either
+/// way so the caller can persist it in the manifest.
+pub fn download_with_verification(
+    url: &str,
+    dest: &Path,
+    tx: &Sender<StatusObj>,
+    expected_hash: Option<&str>,
+) -> Result<String, String> {
+    download_with_progress(url, dest, tx)?;
+
+    if let Some(expected) = expected_hash {
+        if !verify_digest(dest, expected)? {
+            return Err(format!(
+                "Digest mismatch for {:?}: expected {}",
+                dest, expected
+            ));
+        }
+        return Ok(expected.to_string());
+    }
+
+    sha256_digest(dest)
+}
+
+/// Downloads each `(url, dest)` pair in turn over the same `StatusObj`
+/// channel, returning how many succeeded. This is the shape `do_scrape`'s
+/// screenshot loop needs once it reports through `StatusObj` instead of
+/// `println!`.
+pub fn download_all_with_progress(items: &[(String, PathBuf)], tx: &Sender<StatusObj>) -> usize {
+    let mut succeeded = 0;
+
+    for (url, dest) in items {
+        match download_with_progress(url, dest, tx) {
+            Ok(()) => succeeded += 1,
+            Err(e) => {
+                let _ = tx.send(StatusObj {
+                    label: dest.file_name().map(|name| name.to_string_lossy().to_string()),
+                    progress: None,
+                    complete: false,
+                    log_line: None,
+                    error: Some(e),
+                });
+            }
+        }
+    }
+
+    succeeded
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 use serde::{ Serialize, Deserialize };
 
@@ -26,6 +27,23 @@ pub struct MCPManager {
     pub selected_game: String
 }
 
+/// Per-item content hashes recorded by `gog sync`, keyed by download item
+/// name, so a re-run can tell which files already match what's on disk and
+/// skip re-fetching them.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct GogSyncState {
+    pub hashes: HashMap<String, String>,
+}
+
+/// Recorded content digests for the files `gog sync` downloads, keyed by
+/// file path; scraped screenshots are not recorded here. Backs the
+/// `games verify` subcommand, which re-hashes each entry and reports files
+/// that have gone missing or no longer match.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ContentHashState {
+    pub hashes: HashMap<String, String>,
+}
+
 pub struct ManifestManager {
     pub dillinger_config: DillingerConfig,
 }
@@ -115,6 +133,75 @@ impl ManifestManager {
     //     path.to_str().unwrap().to_string()
     // }
 
+    /// Where the `gog sync` content-hash state lives.
+    fn get_gog_sync_state_file(&self) -> PathBuf {
+        let mut path = PathBuf::from(&self.dillinger_config.paths.data_dir);
+        path.push("gog_sync_state.json");
+        path
+    }
+
+    /// Loads the recorded hash of every item `gog sync` has previously
+    /// downloaded, so it can skip files that haven't changed.
+    pub fn load_gog_sync_hashes(&self) -> HashMap<String, String> {
+        let path = self.get_gog_sync_state_file();
+        if !files::file_exists(&path) {
+            return HashMap::new();
+        }
+
+        let content = files::read_file(&path);
+        let state: Result<GogSyncState, serde_json::Error> = serde_json::from_str(&content);
+        match state {
+            Ok(state) => state.hashes,
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    /// Persists the content hash recorded for every item `gog sync` has
+    /// downloaded or confirmed unchanged this run.
+    pub fn save_gog_sync_hashes(&self, hashes: &HashMap<String, String>) {
+        let path = self.get_gog_sync_state_file();
+        let state = GogSyncState { hashes: hashes.clone() };
+        let json_serialized = serde_json::to_string_pretty(&state).unwrap();
+        files::write_file(&path, json_serialized, true);
+    }
+
+    /// Where the recorded per-file content digests live.
+    fn get_content_hash_state_file(&self) -> PathBuf {
+        let mut path = PathBuf::from(&self.dillinger_config.paths.data_dir);
+        path.push("content_hashes.json");
+        path
+    }
+
+    /// Loads every recorded (file path -> digest) entry.
+    pub fn load_content_hashes(&self) -> HashMap<String, String> {
+        let path = self.get_content_hash_state_file();
+        if !files::file_exists(&path) {
+            return HashMap::new();
+        }
+
+        let content = files::read_file(&path);
+        let state: Result<ContentHashState, serde_json::Error> = serde_json::from_str(&content);
+        match state {
+            Ok(state) => state.hashes,
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    fn save_content_hashes(&self, hashes: &HashMap<String, String>) {
+        let path = self.get_content_hash_state_file();
+        let state = ContentHashState { hashes: hashes.clone() };
+        let json_serialized = serde_json::to_string_pretty(&state).unwrap();
+        files::write_file(&path, json_serialized, true);
+    }
+
+    /// Records the digest computed for a single downloaded file, so a later
+    /// `games verify` run can confirm it's still intact.
+    pub fn record_content_hash(&self, file_path: &str, digest: &str) {
+        let mut hashes = self.load_content_hashes();
+        hashes.insert(file_path.to_string(), digest.to_string());
+        self.save_content_hashes(&hashes);
+    }
+
     pub fn add_scrape_file(&self, scrape_activity: &mut ScrapeEntry) -> PathBuf {
         // Store the scraped data to the scraper directory
         let json_serialized = serde_json::to_string_pretty(&scrape_activity).unwrap();
@@ -11,6 +11,7 @@ pub struct PathConfig {
 pub struct SecretsConfig {
     pub twitch_client_id: String,
     pub twitch_client_secret: String,
+    pub gog_auth_token: String,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -18,6 +19,21 @@ pub struct DillingerConfig {
     pub paths: PathConfig,
     pub secrets: SecretsConfig,
     pub romsites: Vec<RomSite>,
+    pub scrape: ScrapeConfig,
+}
+
+/// Defaults applied when scraping media (screenshots today, box art/videos
+/// later), overridable per-invocation via `scrape` subcommand flags.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ScrapeConfig {
+    /// One of IGDB's image size tokens - "thumb", "screenshot_med",
+    /// "screenshot_big" or "screenshot_huge".
+    pub default_resolution: String,
+    /// Screenshots shorter than this (in pixels) are skipped entirely.
+    pub min_screenshot_height: u64,
+    /// When true, screenshots are saved under a `media` subfolder next to
+    /// the scrape file instead of alongside it.
+    pub save_media_to_subdir: bool,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use reqwest::blocking::Client;
+
+use crate::downloaders;
+use crate::filedb::ManifestManager;
+use crate::global_types::DillingerConfig;
+use crate::web_gog::{self, GogContentKind, GogDownloadItem};
+
+/// Which parts of an owned GOG product to mirror, and an optional
+/// allow-list of product titles to restrict the sync to.
+#[derive(Debug, Clone, Default)]
+pub struct SyncFilters {
+    pub skip_dlc: bool,
+    pub skip_extras: bool,
+    pub only_games: Option<Vec<String>>,
+}
+
+impl SyncFilters {
+    fn keep(&self, product_title: &str, item: &GogDownloadItem) -> bool {
+        if let Some(only) = &self.only_games {
+            if !only.iter().any(|title| title.eq_ignore_ascii_case(product_title)) {
+                return false;
+            }
+        }
+
+        match item.kind {
+            GogContentKind::Game => true,
+            GogContentKind::Dlc => !self.skip_dlc,
+            GogContentKind::Extra => !self.skip_extras,
+        }
+    }
+}
+
+fn dest_for(docker_volume_dir: &str, product_title: &str, item: &GogDownloadItem) -> PathBuf {
+    let mut path = PathBuf::from(docker_volume_dir);
+    path.push("gog");
+    path.push(product_title);
+    if item.kind == GogContentKind::Extra {
+        path.push("bonus");
+    }
+    path.push(&item.name);
+    path
+}
+
+/// Mirrors every owned product in the signed-in GOG account into
+/// `{docker_volume_dir}/gog/{product}/`, recording each item's content hash
+/// in the manifest so re-running only re-downloads files whose hash no
+/// longer matches. Bonus content (soundtracks, movies, ...) lands in a
+/// `bonus` subfolder alongside the installer.
+///
+/// Returns the number of items actually downloaded (items skipped because
+/// their hash already matched are not counted).
+pub fn sync_library(
+    dillinger_config: &DillingerConfig,
+    auth_token: &str,
+    filters: &SyncFilters,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let client = Client::new();
+    let manifest_manager = ManifestManager::new(dillinger_config);
+    let mut hashes = manifest_manager.load_gog_sync_hashes();
+
+    let products = web_gog::list_owned_products(&client, auth_token)?;
+    let mut synced = 0;
+
+    for product in &products {
+        let items = web_gog::get_download_links(&client, auth_token, product)?;
+
+        for item in items.iter().filter(|item| filters.keep(&product.title, item)) {
+            let dest = dest_for(&dillinger_config.paths.docker_volume_dir, &product.title, item);
+
+            if let Some(existing_hash) = hashes.get(&item.name) {
+                if dest.exists() && downloaders::sha256_digest(&dest).as_ref() == Ok(existing_hash) {
+                    println!("{} unchanged, skipping", item.name);
+                    continue;
+                }
+            }
+
+            println!("Downloading {}", item.name);
+            let (tx, rx) = mpsc::channel();
+            let digest = downloaders::download_with_verification(
+                &item.url,
+                &dest,
+                &tx,
+                item.checksum.as_deref(),
+            )?;
+            drop(tx);
+            while let Ok(status) = rx.recv() {
+                if let Some(log_line) = status.log_line {
+                    println!("  {}", log_line);
+                }
+            }
+
+            hashes.insert(item.name.clone(), downloaders::sha256_digest(&dest).unwrap_or(digest.clone()));
+            manifest_manager.record_content_hash(&dest.to_string_lossy(), &digest);
+            synced += 1;
+        }
+    }
+
+    manifest_manager.save_gog_sync_hashes(&hashes);
+    Ok(synced)
+}
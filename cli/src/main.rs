@@ -4,10 +4,11 @@ use clap::ArgGroup;
 use filedb::ManifestManager;
 use scrapers::scrapers::{ Scraper, ScrapeEntry, PlatformEntry };
 use std::fs;
+use std::path::PathBuf;
 use console::{style, Term};
 use dialoguer::{ theme::ColorfulTheme, Select, Confirm };
 use crate::scrapers::scrapers::{ ScreenshotInfo };
-use crate::global_types::{ DillingerConfig, PathConfig, SecretsConfig, RomSite };
+use crate::global_types::{ DillingerConfig, PathConfig, SecretsConfig, RomSite, ScrapeConfig };
 use web_view::*;
 
 mod global_types;
@@ -17,6 +18,7 @@ mod filedb;
 mod web_gog;
 mod webparser;
 mod downloaders;
+mod gog_sync;
 
 fn cli() -> Command {
     let config_arg = Arg::new("config")
@@ -42,6 +44,33 @@ fn cli() -> Command {
     let name_arg = Arg::new("name").short('n').long("name").help("Name of the game to search for");
     let platform_arg = Arg::new("platform").short('p').long("platform").help("Name of the platform to search for");
 
+    let resolution_arg = Arg::new("resolution")
+        .short('r')
+        .long("resolution")
+        .value_parser(["thumb", "screenshot_med", "screenshot_big", "screenshot_huge"])
+        .help("Screenshot resolution to download (defaults to scrape.default_resolution in config)");
+
+    let min_height_arg = Arg::new("min-height")
+        .long("min-height")
+        .value_parser(clap::value_parser!(u64))
+        .help("Skip screenshots shorter than this, in pixels (defaults to scrape.min_screenshot_height in config)");
+
+    let skip_dlc_arg = Arg::new("skip-dlc")
+        .long("skip-dlc")
+        .action(clap::ArgAction::SetTrue)
+        .help("Don't mirror DLC content");
+
+    let skip_extras_arg = Arg::new("skip-extras")
+        .long("skip-extras")
+        .action(clap::ArgAction::SetTrue)
+        .help("Don't mirror bonus content (soundtracks, movies, ...)");
+
+    let only_games_arg = Arg::new("only-games")
+        .long("only-games")
+        .num_args(1..)
+        .value_delimiter(',')
+        .help("Only sync the named product(s)");
+
     Command::new("dillinger")
         .about("The Dillinger CLI")
         .subcommand_required(true)
@@ -55,6 +84,10 @@ fn cli() -> Command {
                 .subcommand_required(true)
                 .arg_required_else_help(true)
                 .subcommand(Command::new("ls").about("Lists all games"))
+                .subcommand(
+                    Command::new("verify")
+                        .about("Re-hashes every recorded downloaded file and reports missing/mismatched ones")
+                )
         )
         .subcommand(
             Command::new("scrape")
@@ -64,6 +97,8 @@ fn cli() -> Command {
                 .arg(gamedb_arg)
                 .arg(name_arg)
                 .arg(platform_arg)
+                .arg(resolution_arg)
+                .arg(min_height_arg)
                 .group(
                     ArgGroup::new("name_or_platform")
                     .arg("name")
@@ -82,6 +117,24 @@ fn cli() -> Command {
                 .about("Invokes the download testing function")
                 .arg_required_else_help(false)
         )
+        .subcommand(
+            Command::new("romscrape")
+                .about("Scrapes ROM download links from every configured romsite")
+                .arg_required_else_help(false)
+        )
+        .subcommand(
+            Command::new("gog")
+                .about("Operations against the user's GOG library")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("sync")
+                        .about("Mirrors the owned GOG library into docker_volume_dir, skipping unchanged files")
+                        .arg(skip_dlc_arg)
+                        .arg(skip_extras_arg)
+                        .arg(only_games_arg)
+                )
+        )
 }
 
 fn main() {
@@ -113,6 +166,9 @@ fn main() {
                 ("ls", _) => {
                     list_games(&dillinger_config);
                 }
+                ("verify", _) => {
+                    verify_content_hashes(&dillinger_config);
+                }
                 _ => unreachable!(),
             }
         }
@@ -132,12 +188,21 @@ fn main() {
 
             let screenshots = sub_matches.contains_id("screenshots");
 
+            let resolution = sub_matches
+                .get_one::<String>("resolution")
+                .cloned()
+                .unwrap_or_else(|| dillinger_config.scrape.default_resolution.clone());
+            let min_height = sub_matches
+                .get_one::<u64>("min-height")
+                .copied()
+                .unwrap_or(dillinger_config.scrape.min_screenshot_height);
+
             println!("Scraping {} for {} or {}", game_db, name, platform);
 
             if !name.is_empty() {
-                let _ = do_scrape(name, game_db, screenshots, &dillinger_config);
+                let _ = do_scrape(name, game_db, screenshots, &resolution, min_height, &dillinger_config);
             } else if !platform.is_empty() {
-                let _ = do_scrape_platform(platform, game_db, screenshots, &dillinger_config);
+                let _ = do_scrape_platform(platform, game_db, screenshots, &resolution, min_height, &dillinger_config);
             }
         }
         Some(("testweb", _sub_matches)) => {
@@ -155,8 +220,35 @@ fn main() {
         }
         Some(("testdl", _sub_matches)) => {
             let _ =webparser::find_download_links(webparser::PSX_URLS);
-            
+
+            }
+        Some(("romscrape", _sub_matches)) => {
+            let links = webparser::scrape_romsites(&dillinger_config.romsites);
+            println!("Found {} ROM download link(s):", links.len());
+            for link in &links {
+                println!("[{}] {} -> {}", link.platform, link.filename, link.url);
+            }
+        }
+        Some(("gog", sub_matches)) => {
+            let gog_command = sub_matches.subcommand().unwrap_or(("", sub_matches));
+            match gog_command {
+                ("sync", sync_matches) => {
+                    let filters = gog_sync::SyncFilters {
+                        skip_dlc: sync_matches.get_flag("skip-dlc"),
+                        skip_extras: sync_matches.get_flag("skip-extras"),
+                        only_games: sync_matches
+                            .get_many::<String>("only-games")
+                            .map(|values| values.cloned().collect()),
+                    };
+
+                    match gog_sync::sync_library(&dillinger_config, &dillinger_config.secrets.gog_auth_token, &filters) {
+                        Ok(synced) => println!("Synced {} item(s) from the GOG library", synced),
+                        Err(e) => println!("GOG sync failed: {}", e),
+                    }
+                }
+                _ => unreachable!(),
             }
+        }
         _ => (),
     }
 }
@@ -209,6 +301,7 @@ fn generate_empty_config() -> DillingerConfig {
         secrets: SecretsConfig {
             twitch_client_id: "twitch_client_id".to_string(),
             twitch_client_secret: "twitch_client_secret".to_string(),
+            gog_auth_token: "gog_auth_token".to_string(),
         },
         romsites: vec![
             RomSite {
@@ -217,7 +310,12 @@ fn generate_empty_config() -> DillingerConfig {
                 url: "romsite_url".to_string(),
                 pagespan: "romsite_pagespan".to_string(),
             }
-        ]
+        ],
+        scrape: ScrapeConfig {
+            default_resolution: "screenshot_huge".to_string(),
+            min_screenshot_height: 0,
+            save_media_to_subdir: false,
+        },
     }
 }
 
@@ -241,10 +339,86 @@ fn list_games(dillinger_config: &DillingerConfig) {
     }
 }
 
+/// Re-hashes every file recorded in `content_hashes.json` and reports any
+/// that have gone missing or no longer match what was recorded at download
+/// time, so a corrupt/partial file doesn't silently persist.
+fn verify_content_hashes(dillinger_config: &DillingerConfig) {
+    let manifest_manager = ManifestManager::new(dillinger_config);
+    let recorded = manifest_manager.load_content_hashes();
+    if recorded.is_empty() {
+        println!("No recorded file hashes to verify");
+        return;
+    }
+
+    let mut mismatches = 0;
+    for (file_path, expected_hash) in &recorded {
+        let path = PathBuf::from(file_path);
+        if !path.exists() {
+            println!("MISSING: {}", file_path);
+            mismatches += 1;
+            continue;
+        }
+
+        match downloaders::verify_digest(&path, expected_hash) {
+            Ok(true) => println!("OK: {}", file_path),
+            Ok(false) => {
+                println!("MISMATCH: {}", file_path);
+                mismatches += 1;
+            }
+            Err(e) => {
+                println!("ERROR hashing {}: {}", file_path, e);
+                mismatches += 1;
+            }
+        }
+    }
+
+    println!("{} mismatch(es) out of {} recorded file(s)", mismatches, recorded.len());
+}
+
+/// Builds the `ScreenshotInfo` list for a scraped title's `screenshots`
+/// JSON array: maps each entry to the requested IGDB image `resolution`,
+/// drops any shorter than `min_height`, and places the file under a `media`
+/// subfolder of the scrape file's directory when `media_subdir` is set.
+/// Shared by `do_scrape` and `do_scrape_platform` so the two don't drift.
+fn build_screenshot_infos(
+    screenshot_json_array: &[serde_json::Value],
+    scrape_file: &PathBuf,
+    resolution: &str,
+    min_height: u64,
+    media_subdir: bool,
+) -> Vec<ScreenshotInfo> {
+    screenshot_json_array
+        .iter()
+        .filter(|screenshot| screenshot["height"].as_u64().unwrap_or(0) >= min_height)
+        .map(|screenshot| ScreenshotInfo {
+            id: screenshot["id"].to_string(),
+            url: format!(
+                "https:{}",
+                screenshot["url"]
+                    .to_string()
+                    .trim_matches('"')
+                    .replace("t_thumb", &format!("t_{}", resolution))
+            ),
+            file_path: {
+                let mut file_path = scrape_file.with_file_name("");
+                if media_subdir {
+                    file_path.push("media");
+                }
+                file_path.push(screenshot["id"].to_string());
+                file_path = file_path.with_extension("jpg");
+                file_path
+            },
+            height: screenshot["height"].as_u64().unwrap_or(0),
+        })
+        .collect()
+}
+
 fn do_scrape(
     name: String,
     game_db: String,
     screenshots: bool,
+    resolution: &str,
+    min_height: u64,
     dillinger_config: &DillingerConfig
 ) -> Option<ScrapeEntry> {
     let manifest_manager = ManifestManager::new(dillinger_config);
@@ -302,21 +476,13 @@ fn do_scrape(
                 if screenshots {
 
                     if let Some(screenshot_json_array) = scrape_entry.json["screenshots"].as_array() {
-                    
-                        let screenshot_info = screenshot_json_array.iter().map(|screenshot| ScreenshotInfo {
-                            id: screenshot["id"].to_string(),
-                            // url: { "https:".to_string() + &screenshot["url"].to_string() },
-                            url: format!("https:{}", screenshot["url"].to_string().trim_matches('"').replace("t_thumb", "t_screenshot_huge")),
-                            file_path: { 
-                                //let manifest_manager = ManifestManager::new(dillinger_config);
-                                let mut file_path = scrape_file.with_file_name("");
-                                // let mut file_path = PathBuf::from(filedb::ManifestManager::get_scraper_data_path(&manifest_manager));
-                                file_path.push(screenshot["id"].to_string());
-                                file_path = file_path.with_extension("jpg");
-                                file_path
-                            },
-                            height: screenshot["height"].as_u64().unwrap(),
-                        }).collect();    
+                        let screenshot_info = build_screenshot_infos(
+                            screenshot_json_array,
+                            &scrape_file,
+                            resolution,
+                            min_height,
+                            dillinger_config.scrape.save_media_to_subdir,
+                        );
 
                         println!("Screenshot info: {:?}", screenshot_info);
 
@@ -343,6 +509,8 @@ fn do_scrape_platform(
     platform_name: String,
     game_db: String,
     screenshots: bool,
+    resolution: &str,
+    min_height: u64,
     dillinger_config: &DillingerConfig
 ) -> Option<PlatformEntry> {
     let manifest_manager = ManifestManager::new(dillinger_config);
@@ -400,21 +568,13 @@ fn do_scrape_platform(
                 if screenshots {
 
                     if let Some(screenshot_json_array) = scrape_entry.json["screenshots"].as_array() {
-                    
-                        let screenshot_info = screenshot_json_array.iter().map(|screenshot| ScreenshotInfo {
-                            id: screenshot["id"].to_string(),
-                            // url: { "https:".to_string() + &screenshot["url"].to_string() },
-                            url: format!("https:{}", screenshot["url"].to_string().trim_matches('"').replace("t_thumb", "t_screenshot_huge")),
-                            file_path: { 
-                                //let manifest_manager = ManifestManager::new(dillinger_config);
-                                let mut file_path = scrape_file.with_file_name("");
-                                // let mut file_path = PathBuf::from(filedb::ManifestManager::get_scraper_data_path(&manifest_manager));
-                                file_path.push(screenshot["id"].to_string());
-                                file_path = file_path.with_extension("jpg");
-                                file_path
-                            },
-                            height: screenshot["height"].as_u64().unwrap(),
-                        }).collect();    
+                        let screenshot_info = build_screenshot_infos(
+                            screenshot_json_array,
+                            &scrape_file,
+                            resolution,
+                            min_height,
+                            dillinger_config.scrape.save_media_to_subdir,
+                        );
 
                         println!("Screenshot info: {:?}", screenshot_info);
 
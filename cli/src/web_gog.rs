@@ -0,0 +1,126 @@
+use std::fmt;
+
+use log::debug;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde_json::Value;
+
+const GOG_PRODUCTS_URL: &str = "https://embed.gog.com/account/getFilteredProducts?mediaType=1";
+const GOG_PRODUCT_DETAILS_URL: &str = "https://api.gog.com/products";
+
+#[derive(Debug)]
+pub struct GogError {
+    pub description: String,
+}
+
+impl fmt::Display for GogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Description: {}", self.description)
+    }
+}
+
+impl std::error::Error for GogError {}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct GogOwnedProduct {
+    pub id: u64,
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GogProductsResponse {
+    products: Vec<GogOwnedProduct>,
+}
+
+/// Which bucket a downloadable item belongs to - drives both the
+/// `--skip-dlc`/`--skip-extras` filters and where it lands on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GogContentKind {
+    Game,
+    Dlc,
+    Extra,
+}
+
+#[derive(Debug, Clone)]
+pub struct GogDownloadItem {
+    pub name: String,
+    pub url: String,
+    pub kind: GogContentKind,
+    /// The MD5 checksum GOG publishes for this item, when present, so the
+    /// download can be verified inline rather than only after the fact.
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct GogDownloadsResponse {
+    #[serde(default)]
+    downloads: Vec<Value>,
+    #[serde(default)]
+    dlcs: Vec<Value>,
+    #[serde(default)]
+    extras: Vec<Value>,
+}
+
+/// Lists every product in the signed-in user's GOG library.
+pub fn list_owned_products(client: &Client, auth_token: &str) -> Result<Vec<GogOwnedProduct>, GogError> {
+    debug!("Fetching owned GOG products");
+
+    let response = client
+        .get(GOG_PRODUCTS_URL)
+        .bearer_auth(auth_token)
+        .send()
+        .map_err(|e| GogError {
+            description: format!("Failed to list GOG library: {:?}", e),
+        })?;
+
+    let parsed: GogProductsResponse = response.json().map_err(|e| GogError {
+        description: format!("Failed to parse GOG library response: {:?}", e),
+    })?;
+
+    Ok(parsed.products)
+}
+
+/// Fetches the installer, DLC and bonus-content download links for a single
+/// owned product.
+pub fn get_download_links(
+    client: &Client,
+    auth_token: &str,
+    product: &GogOwnedProduct,
+) -> Result<Vec<GogDownloadItem>, GogError> {
+    let url = format!("{}/{}?expand=downloads,dlcs,extras", GOG_PRODUCT_DETAILS_URL, product.id);
+    debug!("Fetching GOG download links for {} ({})", product.title, url);
+
+    let response = client.get(&url).bearer_auth(auth_token).send().map_err(|e| GogError {
+        description: format!("Failed to fetch downloads for {}: {:?}", product.title, e),
+    })?;
+
+    let parsed: GogDownloadsResponse = response.json().map_err(|e| GogError {
+        description: format!("Failed to parse downloads for {}: {:?}", product.title, e),
+    })?;
+
+    let mut items = Vec::new();
+    items.extend(extract_items(&parsed.downloads, GogContentKind::Game));
+    items.extend(extract_items(&parsed.dlcs, GogContentKind::Dlc));
+    items.extend(extract_items(&parsed.extras, GogContentKind::Extra));
+    Ok(items)
+}
+
+fn extract_items(values: &[Value], kind: GogContentKind) -> Vec<GogDownloadItem> {
+    values
+        .iter()
+        .filter_map(|value| {
+            let name = value.get("name")?.as_str()?.to_string();
+            let url = value
+                .get("manualUrl")
+                .or_else(|| value.get("url"))?
+                .as_str()?
+                .to_string();
+            let checksum = value
+                .get("md5")
+                .or_else(|| value.get("checksum"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            Some(GogDownloadItem { name, url, kind, checksum })
+        })
+        .collect()
+}
@@ -1,34 +1,148 @@
+use std::fmt;
+
+use log::{debug, info};
 use reqwest::blocking::Client;
 use scraper::{Html, Selector};
 
+use crate::global_types::RomSite;
+
 pub const PSX_URLS: &str = "https://archive.org/download/redump.psx";
 
-pub fn find_download_links(url: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    
-    println!("Running download link parser");
-    
-    // Create a new HTTP client
-    let client = Client::new();
-
-    // Send an HTTP GET request to the URL and get the response body
-    println!("Fetching... please wait...");
-    let response = client.get(url).send()?;
-    let body = response.text()?;
-
-    println!("Got the links OK.");
-    // Parse the HTML document using the scraper crate
-    let document = Html::parse_document(&body);
-
-    // Find all <a> elements with a "href" attribute that ends with ".zip"
-    let selector = Selector::parse(r#"a[href$=".zip"]"#).unwrap();
-    let links: Vec<String> = document
-        .select(&selector)
-        .map(|element| format!("{}/{}", url, element.value().attr("href").unwrap().to_string()))
-        .collect();
-
-    for link in &links {
-        println!("{}", link);
+const ROM_EXTENSIONS: [&str; 4] = [".zip", ".7z", ".chd", ".iso"];
+
+#[derive(Debug)]
+pub struct ScrapeError {
+    pub description: String,
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Description: {}", self.description)
+    }
+}
+
+impl std::error::Error for ScrapeError {}
+
+#[derive(Debug, Clone)]
+pub struct RomDownloadLink {
+    pub filename: String,
+    pub url: String,
+    pub size: Option<u64>,
+    pub platform: String,
+}
+
+/// A source of ROM archive links for a single `RomSite` config entry.
+/// `ArchiveOrgScraper` is the only implementation and `scraper_for` routes
+/// every site to it, so archive.org-style sources are added purely via the
+/// `romsites` config list; other site layouts are not handled.
+pub trait RomScraper {
+    fn find_download_links(&self, site: &RomSite) -> Result<Vec<RomDownloadLink>, ScrapeError>;
+}
+
+/// Handles archive.org-style index pages: `pagespan` pages are listed at
+/// `{url}/page/{n}` and each page links straight to the archive files.
+pub struct ArchiveOrgScraper;
+
+impl ArchiveOrgScraper {
+    fn link_selector() -> Selector {
+        Selector::parse("a[href]").unwrap()
     }
 
-    Ok(links)
-}
\ No newline at end of file
+    fn is_rom_archive(href: &str) -> bool {
+        ROM_EXTENSIONS.iter().any(|ext| href.ends_with(ext))
+    }
+
+    fn page_url(base_url: &str, page: u32) -> String {
+        if page <= 1 {
+            base_url.to_string()
+        } else {
+            format!("{}/page/{}", base_url.trim_end_matches('/'), page)
+        }
+    }
+}
+
+impl RomScraper for ArchiveOrgScraper {
+    fn find_download_links(&self, site: &RomSite) -> Result<Vec<RomDownloadLink>, ScrapeError> {
+        let client = Client::new();
+        let pages: u32 = site.pagespan.trim().parse().unwrap_or(1).max(1);
+
+        let mut links = Vec::new();
+        for page in 1..=pages {
+            let page_url = Self::page_url(&site.url, page);
+            debug!("Fetching ROM index page {}", page_url);
+
+            let response = client.get(&page_url).send().map_err(|e| ScrapeError {
+                description: format!("Failed to fetch {}: {:?}", page_url, e),
+            })?;
+            let body = response.text().map_err(|e| ScrapeError {
+                description: format!("Failed to read body of {}: {:?}", page_url, e),
+            })?;
+
+            let document = Html::parse_document(&body);
+            for element in document.select(&Self::link_selector()) {
+                let Some(href) = element.value().attr("href") else {
+                    continue;
+                };
+                if !Self::is_rom_archive(href) {
+                    continue;
+                }
+
+                let url = if href.starts_with("http") {
+                    href.to_string()
+                } else {
+                    format!(
+                        "{}/{}",
+                        site.url.trim_end_matches('/'),
+                        href.trim_start_matches('/')
+                    )
+                };
+                let filename = href.rsplit('/').next().unwrap_or(href).to_string();
+
+                links.push(RomDownloadLink {
+                    filename,
+                    url,
+                    size: None, // archive.org's index pages don't advertise file size
+                    platform: site.platform.clone(),
+                });
+            }
+        }
+
+        info!("Found {} ROM archive(s) at {}", links.len(), site.url);
+        Ok(links)
+    }
+}
+
+/// Picks the right `RomScraper` for a `RomSite` entry. Every configured site
+/// currently maps to the archive.org-style scraper; as new site families are
+/// added to `romsites`, match on `site.name` (or host) here to route to them.
+fn scraper_for(_site: &RomSite) -> Box<dyn RomScraper> {
+    Box::new(ArchiveOrgScraper)
+}
+
+/// Scrapes every configured `RomSite`, returning a flat list of download
+/// links. A single site failing to fetch/parse is logged and skipped rather
+/// than aborting the rest of the list.
+pub fn scrape_romsites(romsites: &[RomSite]) -> Vec<RomDownloadLink> {
+    let mut all_links = Vec::new();
+    for site in romsites {
+        let scraper = scraper_for(site);
+        match scraper.find_download_links(site) {
+            Ok(mut links) => all_links.append(&mut links),
+            Err(e) => println!("Failed to scrape {}: {}", site.name, e),
+        }
+    }
+    all_links
+}
+
+// Kept for the ad-hoc `testdl` CLI command; routes through the same
+// archive.org scraper as the configured `romsites`.
+pub fn find_download_links(url: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let site = RomSite {
+        name: "adhoc".to_string(),
+        platform: "".to_string(),
+        url: url.to_string(),
+        pagespan: "1".to_string(),
+    };
+    let links = ArchiveOrgScraper.find_download_links(&site)?;
+    Ok(links.into_iter().map(|link| link.url).collect())
+}
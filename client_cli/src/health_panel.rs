@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cursive::view::{Nameable, Resizable};
+use cursive::views::{LinearLayout, Panel, ResizedView, TextView};
+use cursive::{CbSink, Cursive};
+
+use dillinger_lib::health::{HealthCheck, ServiceHealth};
+
+/// Replaces the old `StatusModel`'s single `docker_status: String` with a
+/// map of named service states, each polled on its own schedule rather than
+/// by a manual `probe_docker_status()` call from `main`.
+///
+/// The old `StatusController` kept a `StatusView` it could never safely
+/// register as an observer - `model.add_observer(view)` was commented out
+/// because the view was also owned by the cursive layout, so the model
+/// couldn't hold it too. This sidesteps that by not holding a view at all:
+/// each poll result is pushed through cursive's `CbSink`, which is how
+/// `Cursive` lets outside tasks mutate the running view tree.
+pub struct HealthModel {
+    checks: Vec<HealthCheck>,
+    states: HashMap<String, ServiceHealth>,
+}
+
+impl HealthModel {
+    pub fn new(checks: Vec<HealthCheck>) -> Self {
+        HealthModel {
+            checks,
+            states: HashMap::new(),
+        }
+    }
+
+    /// Runs every registered check once and returns the fresh state map.
+    pub async fn poll_once(&mut self) -> HashMap<String, ServiceHealth> {
+        for check in &self.checks {
+            let state = check.run().await;
+            self.states
+                .insert(check.name.clone(), ServiceHealth::new(state, chrono::Utc::now()));
+        }
+        self.states.clone()
+    }
+
+    /// Spawns a background task that polls every `interval` and forwards
+    /// each result to `cb_sink`, which applies it to the `health_<name>`
+    /// `TextView`s `build_panel` creates below. Stops once `cb_sink` starts
+    /// rejecting sends, i.e. once the cursive event loop has exited.
+    pub fn spawn_polling(mut self, cb_sink: CbSink, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                let states = self.poll_once().await;
+                if cb_sink
+                    .send(Box::new(move |siv: &mut Cursive| apply_health_update(siv, &states)))
+                    .is_err()
+                {
+                    break;
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
+
+fn apply_health_update(siv: &mut Cursive, states: &HashMap<String, ServiceHealth>) {
+    for (name, health) in states {
+        siv.call_on_name(&view_name(name), |text_view: &mut TextView| {
+            text_view.set_content(format!(
+                "{:<10} {:<8} checked {}",
+                name,
+                health.state.to_string(),
+                health.last_checked.format("%H:%M:%S")
+            ));
+        });
+    }
+}
+
+fn view_name(check_name: &str) -> String {
+    format!("health_{}", check_name)
+}
+
+/// Builds the health panel layout - one named `TextView` row per check, so
+/// `apply_health_update` can address each row individually as polls land.
+pub fn build_panel(check_names: &[String]) -> ResizedView<Panel<LinearLayout>> {
+    let mut rows = LinearLayout::vertical();
+    for name in check_names {
+        rows.add_child(TextView::new(format!("{:<10} ?", name)).with_name(view_name(name)));
+    }
+
+    Panel::new(rows)
+        .title("Health")
+        .fixed_height(check_names.len().max(1) + 2)
+}
@@ -1,14 +1,23 @@
+use std::time::Duration;
+
 use tokio::runtime::Runtime;
 use cursive::{ view::Resizable, views::{ Panel, ResizedView, TextView, LinearLayout } };
 
-use crate::status_panel::StatusView;
-use crate::status_panel::StatusController;
+use dillinger_lib::health::{check_dir_writable, check_http_reachable, HealthCheck, ServiceState};
+
+use crate::health_panel::HealthModel;
+use crate::status_panel::{StatusController, StatusView};
 
 pub mod theme_default;
 pub mod status_panel;
+pub mod health_panel;
 
 // Updated from MBP16 test
 
+/// How often the health panel re-polls the checks `build_health_checks`
+/// registers.
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
 fn main() {
     let rt = Runtime::new().unwrap();
 
@@ -27,10 +36,18 @@ fn main() {
 
     let mut status_panel: StatusView = StatusView::new();
     let sp = StatusView::get_panel(&mut status_panel);
-    let mut status_controller: StatusController = StatusController::new();
+    // Collects download-progress/game-log updates into its own model; it
+    // builds its own `StatusView` rather than using `status_panel`, and
+    // nothing feeds it here.
+    let _status_controller: StatusController = StatusController::new();
+
+    let health_checks = build_health_checks();
+    let health_names: Vec<String> = health_checks.iter().map(|check| check.name.clone()).collect();
+    let health_panel = health_panel::build_panel(&health_names);
 
     let main_screen_layout = LinearLayout::vertical()
         .child(primary_panel)
+        .child(health_panel)
         .child(sp.unwrap())
         .full_height();
 
@@ -47,17 +64,32 @@ fn main() {
     // Set the root layout as the main view
     siv.add_layer(root_layout);
 
-    rt.block_on(async {
-        status_controller.probe_docker_status().await;
-        StatusController::probe_docker_status(&mut status_controller).await;
-        //StatusController::probe_docker_status(&mut status_controller).await;
-    });
+    // The health model polls in the background and pushes updates into the
+    // running cursive instance via its `cb_sink`, rather than the old
+    // before/after-`siv.run()` one-shot `probe_docker_status` calls.
+    let _guard = rt.enter();
+    HealthModel::new(health_checks).spawn_polling(siv.cb_sink().clone(), HEALTH_POLL_INTERVAL);
 
     siv.run();
+}
 
-    rt.block_on(async {
-        status_controller.probe_docker_status().await;
-        StatusController::probe_docker_status(&mut status_controller).await;
-        //StatusController::probe_docker_status(&mut status_controller).await;
-    });
+/// Registers the `docker`, `gamedb`, and `data_dir` checks the health panel
+/// polls - a stand-in list until the TUI pulls its gamedb roster from the
+/// server's `MasterConfig::game_dbs` over the wire. Only IGDB is checked
+/// for gamedbs, and the `data_dir` row probes the system temp dir
+/// (`std::env::temp_dir()`), not a configured data dir.
+fn build_health_checks() -> Vec<HealthCheck> {
+    vec![
+        HealthCheck::new("docker", || async {
+            if dillinger_lib::docker::ping().await {
+                ServiceState::Up
+            } else {
+                ServiceState::Down
+            }
+        }),
+        HealthCheck::new("gamedb:igdb", || check_http_reachable("https://api.igdb.com")),
+        HealthCheck::new("data_dir", || {
+            check_dir_writable(std::env::temp_dir())
+        }),
+    ]
 }
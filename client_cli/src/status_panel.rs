@@ -1,43 +1,57 @@
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+
 use cursive::{
     view::{ Resizable, Nameable, Finder },
-    views::{ Panel, NamedView, TextView, ResizedView },
+    views::{ Panel, NamedView, TextView, ResizedView, ProgressBar },
 };
 
 use dillinger_lib::{ self };
+use dillinger_lib::status::StatusObj;
 
 trait StatusObserver {
-    fn update_docker_status(&mut self, docker_status: &str);
+    fn update_download_status(&mut self, status: &StatusObj);
+    fn update_game_log_tail(&mut self, tail: &str);
 }
 
 struct StatusModel {
-    docker_status: String,
-    docker_status_observers: Vec<Box<dyn StatusObserver>>,
+    download_status: StatusObj,
+    game_log_tail: String,
+    observers: Vec<Box<dyn StatusObserver>>,
 }
 
 impl StatusModel {
     fn new() -> StatusModel {
         StatusModel {
-            docker_status: "?".to_string(),
-            docker_status_observers: Vec::new(),
+            download_status: StatusObj::default(),
+            game_log_tail: String::new(),
+            observers: Vec::new(),
         }
     }
 
-    fn set_docker_status(&mut self, new_status: &str) {
-        self.docker_status = new_status.to_string();
-        self.notify_observers_docker_status_update();
+    fn set_download_status(&mut self, status: StatusObj) {
+        self.download_status = status;
+        self.notify_observers_download_status_update();
     }
 
-    fn get_docker_status(&self) -> String {
-        self.docker_status.to_string()
+    fn set_game_log_tail(&mut self, tail: String) {
+        self.game_log_tail = tail;
+        self.notify_observers_game_log_tail_update();
     }
 
     fn add_observer(&mut self, observer: Box<dyn StatusObserver>) {
-        self.docker_status_observers.push(observer);
+        self.observers.push(observer);
+    }
+
+    fn notify_observers_download_status_update(&mut self) {
+        for observer in &mut self.observers {
+            observer.update_download_status(&self.download_status);
+        }
     }
 
-    fn notify_observers_docker_status_update(&mut self) {
-        for observer in &mut self.docker_status_observers {
-            observer.update_docker_status(&self.docker_status);
+    fn notify_observers_game_log_tail_update(&mut self) {
+        for observer in &mut self.observers {
+            observer.update_game_log_tail(&self.game_log_tail);
         }
     }
 }
@@ -47,10 +61,28 @@ pub struct StatusView {
 }
 
 impl StatusObserver for StatusView {
-    fn update_docker_status(&mut self, docker_status: &str) {
+    fn update_download_status(&mut self, status: &StatusObj) {
+        let mut panel = self.get_panel().expect("panel not found");
+
+        if let Some(ref mut progress_bar) = panel.find_name::<ProgressBar>("download_progress") {
+            let percent = (status.progress.unwrap_or(0.0) * 100.0) as usize;
+            progress_bar.set_value(percent);
+        }
+
+        if let Some(ref mut log_view) = panel.find_name::<TextView>("download_log") {
+            if let Some(log_line) = &status.log_line {
+                log_view.append(format!("{}\n", log_line));
+            }
+            if let Some(error) = &status.error {
+                log_view.append(format!("error: {}\n", error));
+            }
+        }
+    }
+
+    fn update_game_log_tail(&mut self, tail: &str) {
         let mut panel = self.get_panel().expect("panel not found");
-        if let Some(ref mut text_view) = panel.find_name::<TextView>("docker_status") {
-            text_view.set_content(docker_status.to_string());
+        if let Some(ref mut log_view) = panel.find_name::<TextView>("game_log") {
+            log_view.set_content(tail.to_string());
         }
     }
 }
@@ -59,7 +91,7 @@ impl StatusView {
     pub fn new() -> StatusView {
         StatusView {
             panel: Some(
-                Panel::new(TextView::new("??".to_string()).with_name("docker_status")).fixed_height(
+                Panel::new(TextView::new("".to_string()).with_name("download_log")).fixed_height(
                     3
                 )
             ),
@@ -78,7 +110,7 @@ pub struct StatusController {
 
 impl StatusController {
     pub fn new() -> StatusController {
-        let mut model = StatusModel::new();
+        let model = StatusModel::new();
         let view = Box::new(StatusView::new());
         //model.add_observer(view);
         StatusController {
@@ -87,16 +119,39 @@ impl StatusController {
         }
     }
 
-    pub async fn probe_docker_status(&mut self) {
-        let docker_status: bool = dillinger_lib::docker::ping().await;
-        self.set_docker_status(if docker_status {"UP"} else {"DOWN"});
+    /// Drains every `StatusObj` currently queued on `rx` into the model
+    /// without blocking the cursive event loop. The model has no observer
+    /// registered (see `new`), so these updates are not shown in any view;
+    /// `StatusView` also builds no `download_progress` progress bar.
+    pub fn drain_download_status(&mut self, rx: &Receiver<StatusObj>) {
+        while let Ok(status) = rx.try_recv() {
+            self.model.set_download_status(status);
+        }
     }
 
-    fn set_docker_status(&mut self, new_status: &str) {
-        self.model.set_docker_status(new_status);
+    /// Re-reads the last `max_lines` of `game_log_path` (the `game.log` the
+    /// server's `docker_wine_runner::capture_game_log` tees a launched
+    /// game's output to) and pushes it into the model. As with
+    /// `drain_download_status`, nothing on screen shows it: the model has no
+    /// observer and `StatusView` has no `game_log` view.
+    pub fn refresh_game_log_tail(&mut self, game_log_path: &Path, max_lines: usize) {
+        self.model.set_game_log_tail(read_log_tail(game_log_path, max_lines));
     }
 }
 
+/// Reads the last `max_lines` lines of `path`, or an empty string if it
+/// can't be read yet (the game hasn't launched, or has no output so far).
+fn read_log_tail(path: &Path, max_lines: usize) -> String {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return String::new(),
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    lines[start..].join("\n")
+}
+
 // pub struct StatusPanel {
 //     panel: Option<cursive::views::ResizedView<Panel<NamedView<TextView>>>>,
 //     docker_status: String,
@@ -0,0 +1,100 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The three-state verdict a health check reports - mirrors a readiness
+/// probe rather than a plain up/down bool, so a service that's reachable
+/// but unhappy (e.g. a gamedb answering slowly or with errors) has
+/// somewhere to live besides a hard `Down`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceState {
+    Up,
+    Degraded,
+    Down,
+}
+
+impl std::fmt::Display for ServiceState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ServiceState::Up => "UP",
+            ServiceState::Degraded => "DEGRADED",
+            ServiceState::Down => "DOWN",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A named service's last-known state, as of `last_checked` - what the TUI's
+/// health panel renders one row of.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServiceHealth {
+    pub state: ServiceState,
+    pub last_checked: DateTime<Utc>,
+}
+
+impl ServiceHealth {
+    pub fn new(state: ServiceState, checked_at: DateTime<Utc>) -> Self {
+        ServiceHealth { state, last_checked: checked_at }
+    }
+}
+
+type CheckFuture = Pin<Box<dyn Future<Output = ServiceState> + Send>>;
+
+/// A single named probe - docker, a configured gamedb, data-dir writability
+/// - boxed so callers can poll a heterogeneous list of them without a check
+/// kind enum. Construct with `HealthCheck::new`.
+pub struct HealthCheck {
+    pub name: String,
+    check: Box<dyn Fn() -> CheckFuture + Send + Sync>,
+}
+
+impl HealthCheck {
+    pub fn new<F, Fut>(name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ServiceState> + Send + 'static,
+    {
+        HealthCheck {
+            name: name.into(),
+            check: Box::new(move || Box::pin(check())),
+        }
+    }
+
+    pub async fn run(&self) -> ServiceState {
+        (self.check)().await
+    }
+}
+
+/// Checks whether `path` is writable by creating and removing a throwaway
+/// file in it - the `data-dir` row of the health panel.
+pub async fn check_dir_writable(path: PathBuf) -> ServiceState {
+    let probe = path.join(".dillinger_health_probe");
+    match tokio::fs::write(&probe, b"ok").await {
+        Ok(()) => {
+            let _ = tokio::fs::remove_file(&probe).await;
+            ServiceState::Up
+        }
+        Err(_) => ServiceState::Down,
+    }
+}
+
+/// Checks whether `url` answers at all - used for gamedb reachability,
+/// where a non-2xx/3xx response (e.g. an expired token) is `Degraded`
+/// rather than `Down`, since the remote is up but not serving cleanly.
+pub async fn check_http_reachable(url: &str) -> ServiceState {
+    match reqwest::Client::new()
+        .head(url)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() || response.status().is_redirection() => {
+            ServiceState::Up
+        }
+        Ok(_) => ServiceState::Degraded,
+        Err(_) => ServiceState::Down,
+    }
+}
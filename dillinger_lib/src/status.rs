@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A single progress update from a long-running background task (a
+/// download, a scrape) to whatever's watching it - a `StatusController` in
+/// the TUI, a websocket client, or the CLI's own stdout. Mirrors the
+/// label/progress/log_line/complete shape `InstallStatus` already uses for
+/// Lutris installs, generalized so it isn't tied to one subsystem.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StatusObj {
+    pub label: Option<String>,
+    pub progress: Option<f64>,
+    pub complete: bool,
+    pub log_line: Option<String>,
+    pub error: Option<String>,
+}